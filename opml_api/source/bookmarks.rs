@@ -0,0 +1,89 @@
+//! Conversion to and from the Netscape bookmarks format, which browsers use
+//! to import and export bookmarks as `bookmarks.html`.
+
+#[cfg(feature = "bookmarks")]
+mod import;
+
+use crate::{Outline, OPML};
+
+impl OPML {
+  /// Converts the document to a Netscape bookmarks HTML file, which can be
+  /// imported by browsers.
+  ///
+  /// Outlines with children become folders (`<DT><H3>`) containing their
+  /// converted children, preserving the nesting. Other outlines become links
+  /// (`<DT><A HREF="...">`) to their `html_url`, or their `xml_url` when they
+  /// don't have one, with their `xml_url` as the `FEEDURL` attribute.
+  /// Outlines without children or URLs are skipped. The
+  /// [`Head`](crate::Head)'s `title` is used as the title of the file.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml");
+  ///
+  /// let html = opml.to_netscape_bookmarks();
+  /// assert!(html.contains(r#"<DT><A HREF="https://blog.rust-lang.org/feed.xml" FEEDURL="https://blog.rust-lang.org/feed.xml">Rust Blog</A>"#));
+  /// ```
+  pub fn to_netscape_bookmarks(&self) -> String {
+    let title = self
+      .head
+      .as_ref()
+      .and_then(|head| head.title.as_deref())
+      .unwrap_or("Bookmarks");
+
+    let mut html = String::from(concat!(
+      "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n",
+      "<!-- This is an automatically generated file.\n",
+      "     It will be read and overwritten.\n",
+      "     DO NOT EDIT! -->\n",
+      "<META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n",
+    ));
+
+    let title = escape_html(title);
+    html.push_str(&format!("<TITLE>{title}</TITLE>\n<H1>{title}</H1>\n"));
+    write_bookmarks(&mut html, &self.body.outlines, 0);
+    html
+  }
+}
+
+/// Writes the outlines as a bookmarks list (`<DL>`) indented by `depth`.
+fn write_bookmarks(html: &mut String, outlines: &[Outline], depth: usize) {
+  let indent = "    ".repeat(depth);
+  html.push_str(&format!("{indent}<DL><p>\n"));
+
+  for outline in outlines {
+    let text = escape_html(&outline.text);
+
+    if !outline.outlines.is_empty() {
+      html.push_str(&format!("{indent}    <DT><H3>{text}</H3>\n"));
+      write_bookmarks(html, &outline.outlines, depth + 1);
+    } else if let Some(url) =
+      outline.html_url.as_ref().or(outline.xml_url.as_ref())
+    {
+      let url = escape_html(url);
+      let feed_url = match &outline.xml_url {
+        Some(xml_url) => format!(" FEEDURL=\"{}\"", escape_html(xml_url)),
+        None => String::new(),
+      };
+      html.push_str(&format!(
+        "{indent}    <DT><A HREF=\"{url}\"{feed_url}>{text}</A>\n"
+      ));
+    }
+  }
+
+  html.push_str(&format!("{indent}</DL><p>\n"));
+}
+
+/// Escapes the characters that have special meaning in HTML text and
+/// attribute values.
+fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
@@ -0,0 +1,407 @@
+//! Parsing of Netscape bookmarks files, enabled with the `bookmarks` feature.
+
+use crate::{Body, Error, Head, Outline, OPML};
+
+impl OPML {
+  /// Parses a Netscape bookmarks HTML file, like the `bookmarks.html` files
+  /// that browsers export, into a document.
+  ///
+  /// Folders (`<H3>` followed by a `<DL>` list) become outlines with the
+  /// folder's bookmarks as children, preserving the nesting. Links (`<A>`)
+  /// become outlines with their `HREF` as `html_url` and, when present, their
+  /// `FEEDURL` as `xml_url`. An `HREF` that is the same as the `FEEDURL` is
+  /// only used as `xml_url`, since [`OPML::to_netscape_bookmarks`] falls back
+  /// to it for feeds without an `html_url`. The `<TITLE>` is used as the
+  /// [`Head`]'s title.
+  ///
+  /// Returns [`Error::InvalidBookmarks`] when a tag or comment isn't closed,
+  /// a link, folder name or title isn't closed before the next one starts or
+  /// its list ends, the `<DL>` lists aren't balanced, or there is no list or
+  /// no bookmark or folder at all.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let html = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+  /// <TITLE>Bookmarks</TITLE>
+  /// <DL><p>
+  ///   <DT><H3>Rust</H3>
+  ///   <DL><p>
+  ///     <DT><A HREF="https://blog.rust-lang.org/" FEEDURL="https://blog.rust-lang.org/feed.xml">Rust Blog</A>
+  ///   </DL><p>
+  /// </DL><p>"#;
+  ///
+  /// let opml = OPML::from_netscape_bookmarks(html).unwrap();
+  /// let feed = opml.outline_at(&[0, 0]).unwrap();
+  /// assert_eq!(feed.text, "Rust Blog");
+  /// assert_eq!(feed.xml_url.as_deref(), Some("https://blog.rust-lang.org/feed.xml"));
+  /// ```
+  pub fn from_netscape_bookmarks(html: &str) -> Result<Self, Error> {
+    let mut title = None;
+    let mut outlines = vec![];
+    let mut found_list = false;
+
+    // The lists that are currently open, the last one being the deepest.
+    let mut lists: Vec<List> = vec![];
+    // A folder whose `<H3>` has been read but whose `<DL>` hasn't started yet.
+    let mut pending_folder: Option<Outline> = None;
+    // The element whose text is currently being read.
+    let mut capture: Option<Capture> = None;
+
+    for token in tokenize(html)? {
+      match token {
+        Token::Text(text) => {
+          if let Some(capture) = &mut capture {
+            capture.text.push_str(&decode_entities(text));
+          }
+        }
+
+        Token::Start { name, attributes } => match name.as_str() {
+          "dl" => {
+            found_list = true;
+            lists.push(List {
+              folder: pending_folder.take(),
+              outlines: vec![],
+            });
+          }
+          "a" | "h3" | "title" => {
+            ensure_closed(&capture, &format!("<{}>", name.to_uppercase()))?;
+
+            let attribute = |key: &str| {
+              attributes
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.clone())
+            };
+
+            capture = Some(Capture {
+              kind: name.clone(),
+              text: String::new(),
+              href: attribute("href"),
+              feed_url: attribute("feedurl"),
+            });
+          }
+          _ => (),
+        },
+
+        Token::End(name) => match name.as_str() {
+          "dl" => {
+            ensure_closed(&capture, "</DL>")?;
+
+            let mut list = lists.pop().ok_or_else(|| {
+              invalid("found a </DL> without a matching <DL>".to_string())
+            })?;
+
+            if let Some(folder) = pending_folder.take() {
+              list.outlines.push(folder);
+            }
+
+            let list_outlines = match list.folder {
+              Some(mut folder) => {
+                folder.outlines = list.outlines;
+                vec![folder]
+              }
+              None => list.outlines,
+            };
+
+            match lists.last_mut() {
+              Some(parent) => parent.outlines.extend(list_outlines),
+              None => outlines.extend(list_outlines),
+            }
+          }
+          "a" | "h3" | "title" => {
+            let Some(captured) = capture.take() else {
+              continue;
+            };
+
+            let text = captured.text.trim().to_string();
+            match captured.kind.as_str() {
+              "title" => title = Some(text),
+              "h3" => {
+                if let Some(folder) = pending_folder.take() {
+                  current_list(&mut lists)?.outlines.push(folder);
+                }
+
+                pending_folder = Some(Outline {
+                  text,
+                  ..Outline::default()
+                });
+              }
+              _ => {
+                let list = current_list(&mut lists)?;
+                if let Some(folder) = pending_folder.take() {
+                  list.outlines.push(folder);
+                }
+
+                let html_url = captured
+                  .href
+                  .filter(|href| Some(href) != captured.feed_url.as_ref());
+                list.outlines.push(Outline {
+                  text,
+                  xml_url: captured.feed_url,
+                  html_url,
+                  ..Outline::default()
+                });
+              }
+            }
+          }
+          _ => (),
+        },
+      }
+    }
+
+    ensure_closed(&capture, "the end of the file")?;
+
+    if !found_list {
+      return Err(invalid("no bookmarks list (<DL>) found".to_string()));
+    }
+
+    if !lists.is_empty() {
+      return Err(invalid(format!("{} unclosed <DL> list(s)", lists.len())));
+    }
+
+    // SPEC: A `<body>` contains one or more `<outline>` elements.
+    if outlines.is_empty() {
+      return Err(invalid("no bookmarks found".to_string()));
+    }
+
+    Ok(OPML {
+      head: Some(Head {
+        title,
+        ..Head::default()
+      }),
+      body: Body { outlines },
+      ..OPML::default()
+    })
+  }
+}
+
+/// A `<DL>` list of bookmarks that is being parsed.
+struct List {
+  /// The folder the list belongs to, [`None`] for the top-level list.
+  folder: Option<Outline>,
+  /// The bookmarks in the list.
+  outlines: Vec<Outline>,
+}
+
+/// An element whose text content is being read.
+struct Capture {
+  /// The lowercase name of the element.
+  kind: String,
+  /// The text that has been read so far.
+  text: String,
+  /// The `HREF` attribute of the element.
+  href: Option<String>,
+  /// The `FEEDURL` attribute of the element.
+  feed_url: Option<String>,
+}
+
+/// The parts of a bookmarks file, comments and declarations are skipped.
+enum Token<'a> {
+  /// A start tag with its lowercase name and attributes.
+  Start {
+    /// The lowercase name of the tag.
+    name: String,
+    /// The lowercase names and decoded values of the attributes.
+    attributes: Vec<(String, String)>,
+  },
+  /// An end tag with its lowercase name.
+  End(String),
+  /// Text between tags, with entities still encoded.
+  Text(&'a str),
+}
+
+/// Returns the list that is currently open.
+fn current_list(lists: &mut [List]) -> Result<&mut List, Error> {
+  lists
+    .last_mut()
+    .ok_or_else(|| invalid("found a bookmark outside of a <DL> list".into()))
+}
+
+/// Returns an error when an element is still being read, so it isn't
+/// silently dropped when `next` is found before it was closed.
+fn ensure_closed(capture: &Option<Capture>, next: &str) -> Result<(), Error> {
+  match capture {
+    Some(capture) => Err(invalid(format!(
+      "found {next} before <{}> was closed",
+      capture.kind.to_uppercase()
+    ))),
+    None => Ok(()),
+  }
+}
+
+/// Creates an [`Error::InvalidBookmarks`] with the given message.
+fn invalid(message: String) -> Error {
+  Error::InvalidBookmarks(message)
+}
+
+/// Splits the HTML into its tags and the text between them.
+fn tokenize(html: &str) -> Result<Vec<Token<'_>>, Error> {
+  let mut tokens = vec![];
+  let mut rest = html;
+  let mut offset = 0;
+
+  while let Some(start) = rest.find('<') {
+    if start > 0 {
+      tokens.push(Token::Text(&rest[..start]));
+    }
+
+    let tag = &rest[start..];
+    let position = offset + start;
+
+    let end = if tag.starts_with("<!--") {
+      tag.find("-->").map(|end| end + 3)
+    } else {
+      find_tag_end(tag).map(|end| end + 1)
+    }
+    .ok_or_else(|| invalid(format!("unterminated tag at byte {position}")))?;
+
+    if let Some(token) = parse_tag(&tag[1..end - 1]) {
+      tokens.push(token);
+    }
+
+    rest = &tag[end..];
+    offset = position + end;
+  }
+
+  if !rest.is_empty() {
+    tokens.push(Token::Text(rest));
+  }
+
+  Ok(tokens)
+}
+
+/// Finds the `>` that closes the tag at the start of `tag`, skipping the ones
+/// inside quoted attribute values.
+fn find_tag_end(tag: &str) -> Option<usize> {
+  let mut quote = None;
+  // Whether the previous characters are an `=` and optional whitespace, so a
+  // quote starts an attribute value.
+  let mut after_equals = false;
+
+  for (index, character) in tag.char_indices() {
+    if let Some(quote_character) = quote {
+      if character == quote_character {
+        quote = None;
+      }
+      continue;
+    }
+
+    match character {
+      '>' => return Some(index),
+      '"' | '\'' if after_equals => quote = Some(character),
+      _ => (),
+    }
+
+    after_equals =
+      character == '=' || (after_equals && character.is_whitespace());
+  }
+
+  None
+}
+
+/// Parses the content of a tag (without `<` and `>`), returning [`None`] for
+/// comments and declarations like `<!DOCTYPE ...>`.
+fn parse_tag(content: &str) -> Option<Token<'_>> {
+  if content.starts_with(['!', '?']) {
+    return None;
+  }
+
+  if let Some(name) = content.strip_prefix('/') {
+    return Some(Token::End(name.trim().to_ascii_lowercase()));
+  }
+
+  let name_end = content
+    .find(|c: char| c.is_whitespace() || c == '/')
+    .unwrap_or(content.len());
+
+  Some(Token::Start {
+    name: content[..name_end].to_ascii_lowercase(),
+    attributes: parse_attributes(&content[name_end..]),
+  })
+}
+
+/// Parses the attributes of a tag, which can be double-quoted, single-quoted
+/// or unquoted.
+fn parse_attributes(mut rest: &str) -> Vec<(String, String)> {
+  let mut attributes = vec![];
+
+  loop {
+    rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+    if rest.is_empty() {
+      return attributes;
+    }
+
+    let name_end = rest
+      .find(|c: char| c == '=' || c.is_whitespace())
+      .unwrap_or(rest.len());
+    let name = rest[..name_end].to_ascii_lowercase();
+    rest = rest[name_end..].trim_start();
+
+    let mut value = "";
+    if let Some(after) = rest.strip_prefix('=') {
+      let after = after.trim_start();
+
+      match after.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+          let quoted = &after[1..];
+          let close = quoted.find(quote).unwrap_or(quoted.len());
+          value = &quoted[..close];
+          rest = quoted.get(close + 1..).unwrap_or_default();
+        }
+        _ => {
+          let end = after.find(char::is_whitespace).unwrap_or(after.len());
+          value = &after[..end];
+          rest = &after[end..];
+        }
+      }
+    }
+
+    attributes.push((name, decode_entities(value)));
+  }
+}
+
+/// Decodes the named entities that can appear in bookmarks files and numeric
+/// character references. Unknown entities are kept as-is.
+fn decode_entities(text: &str) -> String {
+  let mut decoded = String::with_capacity(text.len());
+  let mut rest = text;
+
+  while let Some(start) = rest.find('&') {
+    decoded.push_str(&rest[..start]);
+    rest = &rest[start..];
+
+    let entity = rest.find(';').map(|end| (&rest[1..end], end));
+    let character = entity.and_then(|(entity, _)| match entity {
+      "amp" => Some('&'),
+      "lt" => Some('<'),
+      "gt" => Some('>'),
+      "quot" => Some('"'),
+      "apos" => Some('\''),
+      _ => {
+        let number = entity.strip_prefix('#')?;
+        let code = match number.strip_prefix(['x', 'X']) {
+          Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+          None => number.parse().ok()?,
+        };
+        char::from_u32(code)
+      }
+    });
+
+    match (character, entity) {
+      (Some(character), Some((_, end))) => {
+        decoded.push(character);
+        rest = &rest[end + 1..];
+      }
+      _ => {
+        decoded.push('&');
+        rest = &rest[1..];
+      }
+    }
+  }
+
+  decoded.push_str(rest);
+  decoded
+}
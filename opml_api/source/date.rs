@@ -0,0 +1,18 @@
+//! Date-time helpers, enabled with the `date` feature.
+
+use chrono::{DateTime, FixedOffset, Utc};
+
+/// The RFC822 format used by the spec's examples, for example
+/// `Mon, 27 Feb 2006 12:09:48 GMT`.
+const RFC822_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// Returns the current date-time formatted as RFC822.
+pub(crate) fn now_rfc822() -> String {
+  Utc::now().format(RFC822_FORMAT).to_string()
+}
+
+/// Parses an RFC822 date-time like `Mon, 27 Feb 2006 12:09:48 GMT`, also
+/// accepting the other forms RFC2822 allows, like numeric time zones.
+pub(crate) fn parse_rfc822(value: &str) -> Option<DateTime<FixedOffset>> {
+  DateTime::parse_from_rfc2822(value.trim()).ok()
+}
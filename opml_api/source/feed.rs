@@ -0,0 +1,62 @@
+//! Integration with the [`feed_rs`] crate, enabled with the `feed-rs` feature.
+
+use feed_rs::model::Feed;
+
+use crate::Outline;
+
+impl Outline {
+  /// Creates an [`Outline`] for a feed that has been parsed with [`feed_rs`].
+  ///
+  /// The fields are mapped as follows:
+  /// * `text` and `title` are set to the feed's title, `text` falls back to
+  ///   `xml_url` when the feed has no title,
+  /// * `html_url` is set to the first link that isn't a `self` link (the
+  ///   `self` link points to the feed itself, which is `xml_url`),
+  /// * `description` is set to the feed's description,
+  /// * `language` is set to the feed's language,
+  /// * `type` is set to `rss`, as the spec requires for subscription lists,
+  /// * `xml_url` is set to the given URL, since the feed doesn't reliably
+  ///   know where it was fetched from.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::Outline;
+  ///
+  /// let xml = r#"<rss version="2.0"><channel>
+  ///   <title>Rust Blog</title>
+  ///   <link>https://blog.rust-lang.org/</link>
+  ///   <description>Empowering everyone to build reliable software.</description>
+  ///   <language>en</language>
+  /// </channel></rss>"#;
+  /// let feed = feed_rs::parser::parse(xml.as_bytes()).unwrap();
+  /// let outline = Outline::from_feed(&feed, "https://blog.rust-lang.org/feed.xml");
+  ///
+  /// assert_eq!(outline.text, "Rust Blog");
+  /// assert_eq!(outline.html_url.as_deref(), Some("https://blog.rust-lang.org/"));
+  /// assert_eq!(outline.language.as_deref(), Some("en"));
+  /// ```
+  pub fn from_feed(feed: &Feed, xml_url: &str) -> Self {
+    let title = feed.title.as_ref().map(|title| title.content.clone());
+
+    let html_url = feed
+      .links
+      .iter()
+      .find(|link| link.rel.as_deref() != Some("self"))
+      .map(|link| link.href.clone());
+
+    Outline {
+      text: title.clone().unwrap_or_else(|| xml_url.to_string()),
+      r#type: Some("rss".to_string()),
+      xml_url: Some(xml_url.to_string()),
+      description: feed
+        .description
+        .as_ref()
+        .map(|description| description.content.clone()),
+      html_url,
+      language: feed.language.clone(),
+      title,
+      ..Outline::default()
+    }
+  }
+}
@@ -0,0 +1,131 @@
+//! [`Arbitrary`] implementations, enabled with the `arbitrary` feature.
+//!
+//! The generated documents are "valid-ish": they always use a supported
+//! version and have at least one outline in the body, strings don't contain
+//! control characters or characters XML doesn't allow, and the outline tree
+//! never goes deeper than [`MAX_DEPTH`] so generation can't overflow the stack.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{Body, Head, Outline, OPML};
+
+/// The maximum nesting depth of generated outlines.
+const MAX_DEPTH: usize = 4;
+
+/// The maximum amount of child outlines generated for a single parent.
+const MAX_CHILDREN: usize = 4;
+
+impl<'a> Arbitrary<'a> for OPML {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    Ok(OPML {
+      version: u.choose(&["1.0", "1.1", "2.0"])?.to_string(),
+      head: Option::<Head>::arbitrary(u)?,
+      body: Body::arbitrary(u)?,
+      source_encoding: None,
+    })
+  }
+}
+
+impl<'a> Arbitrary<'a> for Head {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    Ok(Head {
+      title: arbitrary_optional_string(u)?,
+      date_created: arbitrary_optional_string(u)?,
+      date_modified: arbitrary_optional_string(u)?,
+      owner_name: arbitrary_optional_string(u)?,
+      owner_email: arbitrary_optional_string(u)?,
+      owner_id: arbitrary_optional_string(u)?,
+      docs: arbitrary_optional_string(u)?,
+      expansion_state: arbitrary_optional_string(u)?,
+      vert_scroll_state: u.arbitrary()?,
+      window_top: u.arbitrary()?,
+      window_left: u.arbitrary()?,
+      window_bottom: u.arbitrary()?,
+      window_right: u.arbitrary()?,
+    })
+  }
+}
+
+impl<'a> Arbitrary<'a> for Body {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    // SPEC: A `<body>` contains one or more `<outline>` elements.
+    let count = u.int_in_range(1..=MAX_CHILDREN)?;
+    let outlines = (0..count)
+      .map(|_| arbitrary_outline(u, 1))
+      .collect::<Result<_>>()?;
+
+    Ok(Body { outlines })
+  }
+}
+
+impl<'a> Arbitrary<'a> for Outline {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    arbitrary_outline(u, 1)
+  }
+}
+
+/// Generates an [`Outline`] at the given `depth`, only generating children
+/// while the depth is below [`MAX_DEPTH`].
+fn arbitrary_outline(u: &mut Unstructured, depth: usize) -> Result<Outline> {
+  let count = if depth < MAX_DEPTH {
+    u.int_in_range(0..=MAX_CHILDREN)?
+  } else {
+    0
+  };
+
+  let outlines = (0..count)
+    .map(|_| arbitrary_outline(u, depth + 1))
+    .collect::<Result<_>>()?;
+
+  Ok(Outline {
+    text: arbitrary_string(u)?,
+    r#type: arbitrary_optional_string(u)?,
+    is_comment: u.arbitrary()?,
+    is_breakpoint: u.arbitrary()?,
+    created: arbitrary_optional_string(u)?,
+    category: arbitrary_optional_string(u)?,
+    outlines,
+    xml_url: arbitrary_optional_string(u)?,
+    description: arbitrary_optional_string(u)?,
+    html_url: arbitrary_optional_string(u)?,
+    language: arbitrary_optional_string(u)?,
+    title: arbitrary_optional_string(u)?,
+    version: arbitrary_optional_string(u)?,
+    url: arbitrary_optional_string(u)?,
+    original_index: None,
+  })
+}
+
+/// Generates a [`String`] without control characters, which XML can't
+/// represent reliably, or characters outside of the XML 1.0 `Char` production.
+fn arbitrary_string(u: &mut Unstructured) -> Result<String> {
+  let string = String::arbitrary(u)?;
+  Ok(
+    string
+      .chars()
+      .filter(|c| !c.is_control() && is_xml_char(*c))
+      .collect(),
+  )
+}
+
+/// Returns whether the character matches the XML 1.0 `Char` production.
+fn is_xml_char(c: char) -> bool {
+  matches!(
+    c,
+    '\u{9}'
+      | '\u{A}'
+      | '\u{D}'
+      | '\u{20}'..='\u{D7FF}'
+      | '\u{E000}'..='\u{FFFD}'
+      | '\u{10000}'..='\u{10FFFF}'
+  )
+}
+
+/// Generates an optional [`String`] using [`arbitrary_string`].
+fn arbitrary_optional_string(u: &mut Unstructured) -> Result<Option<String>> {
+  if u.arbitrary()? {
+    arbitrary_string(u).map(Some)
+  } else {
+    Ok(None)
+  }
+}
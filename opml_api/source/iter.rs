@@ -0,0 +1,86 @@
+//! Iterators over the outlines of a document.
+
+use std::slice::{Iter, IterMut};
+
+use crate::Outline;
+
+/// An iterator over all the outlines in a document, including nested ones.
+/// Parent outlines are yielded before their children.
+///
+/// Created with [`OPML::outlines`](crate::OPML::outlines).
+#[derive(Debug, Clone)]
+pub struct Outlines<'a> {
+  /// The iterators of the outline lists that are being walked, the last one
+  /// being the deepest.
+  stack: Vec<Iter<'a, Outline>>,
+}
+
+impl<'a> Outlines<'a> {
+  /// Creates an iterator that walks the given outlines and their children.
+  pub(crate) fn new(outlines: &'a [Outline]) -> Self {
+    Self {
+      stack: vec![outlines.iter()],
+    }
+  }
+}
+
+impl<'a> Iterator for Outlines<'a> {
+  type Item = &'a Outline;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      let outlines = self.stack.last_mut()?;
+
+      match outlines.next() {
+        Some(outline) => {
+          self.stack.push(outline.outlines.iter());
+          return Some(outline);
+        }
+        None => {
+          self.stack.pop();
+        }
+      }
+    }
+  }
+}
+
+/// An iterator over mutable references to the feeds in a document, the
+/// outlines that have an `xml_url`.
+///
+/// Outlines nested inside a feed aren't yielded, since they can't be borrowed
+/// mutably while the feed itself is.
+///
+/// Created with [`OPML::feeds_mut`](crate::OPML::feeds_mut).
+#[derive(Debug)]
+pub struct FeedsMut<'a> {
+  /// The iterators of the outline lists that are being walked, the last one
+  /// being the deepest.
+  stack: Vec<IterMut<'a, Outline>>,
+}
+
+impl<'a> FeedsMut<'a> {
+  /// Creates an iterator that walks the given outlines and their children.
+  pub(crate) fn new(outlines: &'a mut [Outline]) -> Self {
+    Self {
+      stack: vec![outlines.iter_mut()],
+    }
+  }
+}
+
+impl<'a> Iterator for FeedsMut<'a> {
+  type Item = &'a mut Outline;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      let outlines = self.stack.last_mut()?;
+
+      match outlines.next() {
+        Some(outline) if outline.xml_url.is_some() => return Some(outline),
+        Some(outline) => self.stack.push(outline.outlines.iter_mut()),
+        None => {
+          self.stack.pop();
+        }
+      }
+    }
+  }
+}
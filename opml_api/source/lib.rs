@@ -24,18 +24,61 @@
 //! assert_eq!(document.version, "2.0");
 //! ```
 //!
+//! Values that don't follow the spec but don't prevent parsing either can be
+//! found with [`OPML::validate`].
+//!
 //! ## Creating
 //!
 //! To create an OPML document from scratch, use [`OPML::default()`] or the good
 //! old `OPML { /* ... */ }` syntax.
+//!
+//! ## Features
+//!
+//! * `arbitrary`: implements [`arbitrary::Arbitrary`](https://docs.rs/arbitrary)
+//!   for all elements, generating valid-ish documents with bounded depth for
+//!   fuzzing and property tests.
+//! * `bookmarks`: adds `OPML::from_netscape_bookmarks` to import bookmarks
+//!   exported by browsers.
+//! * `date`: adds `OPML::to_writer_touching` to update `dateModified` when
+//!   writing a document.
+//! * `feed-rs`: adds `Outline::from_feed` to create outlines from feeds
+//!   parsed with [`feed_rs`](https://docs.rs/feed-rs).
+//! * `serde-camel-case`: names the [`serde`](https://docs.rs/serde) fields of
+//!   [`Head`] and [`Outline`] after their OPML elements and attributes, like
+//!   `dateCreated` and `xmlUrl`, instead of their snake case Rust names. The
+//!   snake case names are still accepted when deserializing. This changes the
+//!   serialized output, so it's opt-in until the next major version.
+//! * `tracing`: records a [`tracing`](https://docs.rs/tracing) span and a
+//!   debug event for every parsed document, with its size, version, feed
+//!   count and how long parsing took, or the kind of error parsing failed
+//!   with.
 
 #![forbid(unsafe_code)]
 #![warn(missing_docs, clippy::missing_docs_in_private_items)]
 
-use hard_xml::{XmlRead, XmlWrite};
+mod bookmarks;
+#[cfg(feature = "date")]
+mod date;
+#[cfg(feature = "feed-rs")]
+mod feed;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+mod iter;
+mod read;
+#[cfg(feature = "tracing")]
+mod trace;
+mod validation;
+mod write;
+
+use std::collections::{HashMap, HashSet};
+
+use hard_xml::{XmlRead, XmlReader, XmlWrite, XmlWriter};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+pub use iter::{FeedsMut, Outlines};
+pub use validation::ValidationError;
+
 /// All possible errors.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -45,10 +88,54 @@ pub enum Error {
   #[error("OPML body has no <outline> elements")]
   BodyHasNoOutlines,
 
+  /// The input passed to [`OPML::from_bytes`] isn't valid UTF-8.
+  #[error("Invalid UTF-8 at byte {offset}")]
+  Encoding {
+    /// The offset of the first byte that isn't valid UTF-8.
+    offset: usize,
+  },
+
+  /// No outline has the text passed to [`OPML::export_folder`].
+  #[error("No folder named {0:?}")]
+  FolderNotFound(String),
+
+  /// The input isn't a valid Netscape bookmarks file, see
+  /// `OPML::from_netscape_bookmarks` with the `bookmarks` feature.
+  #[error("Invalid bookmarks file: {0}")]
+  InvalidBookmarks(String),
+
+  /// One of the documents passed to [`OPML::from_str_multi`] couldn't be
+  /// parsed.
+  #[error("Invalid document at index {index}")]
+  InvalidDocument {
+    /// The index of the document, starting at 0.
+    index: usize,
+    /// The error the document failed with.
+    source: Box<Error>,
+  },
+
+  /// An outline couldn't be moved with [`OPML::move_outline`].
+  #[error("Invalid move: {0}")]
+  InvalidMove(String),
+
+  /// An index path doesn't point to an outline, see [`OPML::append_outline`].
+  #[error("Invalid path: {0}")]
+  InvalidPath(String),
+
   /// Wrapper for [`std::io::Error`].
   #[error("Failed to read file")]
   IoError(#[from] std::io::Error),
 
+  /// The outlines with the text passed to [`OPML::export_folder`] have no
+  /// children, like feeds and empty folders.
+  #[error("Outline {0:?} is not a folder")]
+  NotAFolder(String),
+
+  /// The document has more outlines than the `max_outlines` of the
+  /// [`ParseOptions`] allow.
+  #[error("OPML document has more than {0} outlines")]
+  TooManyOutlines(usize),
+
   /// The version string in the XML is not supported.
   #[error("Unsupported OPML version: {0:?}")]
   UnsupportedVersion(String),
@@ -59,30 +146,32 @@ pub enum Error {
 }
 
 /// The top-level [`OPML`] element.
-#[derive(
-  XmlWrite, XmlRead, PartialEq, Eq, Debug, Clone, Serialize, Deserialize,
-)]
-#[xml(tag = "opml")]
+#[derive(Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct OPML {
   /// The version attribute from the element, valid values are `1.0`, `1.1` and
-  /// `2.0`.
-  #[xml(attr = "version")]
+  /// `2.0`. Whitespace around the version is trimmed when parsing.
   pub version: String,
 
   /// The [`Head`] child element. Contains the metadata of the OPML document.
-  #[xml(child = "head")]
   pub head: Option<Head>,
 
   /// The [`Body`] child element. Contains all the [`Outline`] elements.
-  #[xml(child = "body")]
   pub body: Body,
+
+  /// The encoding declared in the XML declaration of the parsed document,
+  /// like `ISO-8859-1`, or [`None`] when there was no declaration or it
+  /// didn't specify one. This isn't part of the document, so it isn't
+  /// serialized, written or compared when checking documents for equality.
+  /// To declare it when writing, see [`WriteOptions::declared_encoding`].
+  #[serde(skip)]
+  pub source_encoding: Option<String>,
 }
 
 impl OPML {
   /// Deprecated, use [`OPML::from_str`] instead.
   #[deprecated(note = "Use from_str instead", since = "1.1.0")]
   pub fn new(xml: &str) -> Result<Self, Error> {
-    Self::from_str(xml).map_err(Into::into)
+    Self::from_str(xml)
   }
 
   /// Parses an OPML document.
@@ -105,7 +194,50 @@ impl OPML {
   /// ```
   #[allow(clippy::should_implement_trait)]
   pub fn from_str(xml: &str) -> Result<Self, Error> {
-    let opml = <OPML as XmlRead>::from_str(xml)?;
+    Self::from_str_with_options(xml, &ParseOptions::default())
+  }
+
+  /// Parses an OPML document using the given [`ParseOptions`].
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{OPML, ParseOptions};
+  ///
+  /// let xml = r#"<opml version="2.0"><head/><body><outline text="Feed" xmlurl="https://example.com/"/></body></opml>"#;
+  /// let options = ParseOptions {
+  ///   case_insensitive_attributes: true,
+  ///   ..ParseOptions::default()
+  /// };
+  /// let document = OPML::from_str_with_options(xml, &options).unwrap();
+  ///
+  /// let feed = document.body.outlines.first().unwrap();
+  /// assert_eq!(feed.xml_url.as_deref(), Some("https://example.com/"));
+  /// ```
+  pub fn from_str_with_options(
+    xml: &str,
+    options: &ParseOptions,
+  ) -> Result<Self, Error> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("parse_opml", size = xml.len()).entered();
+    #[cfg(feature = "tracing")]
+    let start = std::time::Instant::now();
+
+    let result = Self::parse(xml, options);
+
+    #[cfg(feature = "tracing")]
+    trace::parsed(&result, start.elapsed());
+
+    result
+  }
+
+  /// Parses and checks a document, see [`OPML::from_str_with_options`].
+  fn parse(xml: &str, options: &ParseOptions) -> Result<Self, Error> {
+    let mut opml = read::read_opml(&mut XmlReader::new(xml), options)?;
+
+    // Incidental whitespace around the version isn't meaningful, so it's
+    // trimmed and the trimmed version is what's stored.
+    opml.version = opml.version.trim().to_string();
 
     // SPEC: The version attribute is a version string, of the form, x.y, where
     // x and y are both numeric strings.
@@ -123,7 +255,47 @@ impl OPML {
     Ok(opml)
   }
 
-  /// Parses an OPML document from a reader.
+  /// Parses multiple OPML documents that have been concatenated, like some
+  /// backup tools do.
+  ///
+  /// Every document ends after its root element and the comments and
+  /// processing instructions following it. The next document starts at the
+  /// first thing that can't follow a root element, like an `<?xml ... ?>`
+  /// declaration or another root element, so processing instructions like
+  /// `<?xml-stylesheet ... ?>` between a declaration and its root element
+  /// belong to the same document.
+  ///
+  /// Returns [`Error::InvalidDocument`] with the index of the first document
+  /// that couldn't be parsed.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let xml = r#"<?xml version="1.0"?>
+  /// <opml version="2.0"><head/><body><outline text="First"/></body></opml>
+  /// <?xml version="1.0"?>
+  /// <opml version="2.0"><head/><body><outline text="Second"/></body></opml>"#;
+  ///
+  /// let documents = OPML::from_str_multi(xml).unwrap();
+  /// assert_eq!(documents.len(), 2);
+  /// assert_eq!(documents[1].body.outlines[0].text, "Second");
+  /// ```
+  pub fn from_str_multi(xml: &str) -> Result<Vec<Self>, Error> {
+    read::split_documents(xml)
+      .into_iter()
+      .enumerate()
+      .map(|(index, document)| {
+        Self::from_str(document).map_err(|error| Error::InvalidDocument {
+          index,
+          source: Box::new(error),
+        })
+      })
+      .collect()
+  }
+
+  /// Parses an OPML document from a reader, see [`OPML::from_bytes`].
   ///
   /// # Example
   ///
@@ -137,9 +309,42 @@ impl OPML {
   where
     R: std::io::Read,
   {
-    let mut s = String::new();
-    reader.read_to_string(&mut s)?;
-    Self::from_str(&s).map_err(Into::into)
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    Self::from_bytes(&bytes)
+  }
+
+  /// Parses an OPML document from UTF-8 bytes, ignoring a leading byte order
+  /// mark. Other encodings aren't supported and have to be converted to UTF-8
+  /// first.
+  ///
+  /// Returns [`Error::Encoding`] with the offset of the first invalid byte
+  /// when the bytes aren't valid UTF-8.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{Error, OPML};
+  ///
+  /// let xml = br#"<opml version="2.0"><head/><body><outline text="Outline"/></body></opml>"#;
+  /// let document = OPML::from_bytes(xml).unwrap();
+  /// assert_eq!(document.body.outlines[0].text, "Outline");
+  ///
+  /// let invalid = b"<opml version=\"2.0\">\xff</opml>";
+  /// assert!(matches!(OPML::from_bytes(invalid), Err(Error::Encoding { offset: 20 })));
+  /// ```
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+    const BOM: &[u8] = b"\xEF\xBB\xBF";
+    let (bom_length, bytes) = match bytes.strip_prefix(BOM) {
+      Some(bytes) => (BOM.len(), bytes),
+      None => (0, bytes),
+    };
+
+    let xml = std::str::from_utf8(bytes).map_err(|error| Error::Encoding {
+      offset: bom_length + error.valid_up_to(),
+    })?;
+
+    Self::from_str(xml)
   }
 
   /// Helper function to add an [`Outline`] element with `text` and `xml_url`
@@ -173,131 +378,1579 @@ impl OPML {
     self
   }
 
-  /// Deprecated, use [`OPML::to_string`] instead.
-  #[deprecated(note = "Use to_string instead", since = "1.1.0")]
-  pub fn to_xml(&self) -> Result<String, Error> {
-    self.to_string()
+  /// Helper function to add an [`Outline`] element with `text`, `xml_url` and
+  /// `type` attributes to the [`Body`], like [`OPML::add_feed`] but also
+  /// recording the type of the feed, like `rss` or `atom`.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{OPML, Outline};
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.add_typed_feed("Feed Name", "https://example.com/", "atom");
+  /// let added_feed = opml.body.outlines.first().unwrap();
+  ///
+  /// let expected_feed = &Outline {
+  ///   text: "Feed Name".to_string(),
+  ///   r#type: Some("atom".to_string()),
+  ///   xml_url: Some("https://example.com/".to_string()),
+  ///   ..Outline::default()
+  /// };
+  ///
+  /// assert_eq!(added_feed, expected_feed);
+  /// ```
+  pub fn add_typed_feed(
+    &mut self,
+    text: &str,
+    url: &str,
+    feed_type: &str,
+  ) -> &mut Self {
+    self.body.outlines.push(Outline {
+      text: text.to_string(),
+      r#type: Some(feed_type.to_string()),
+      xml_url: Some(url.to_string()),
+      ..Outline::default()
+    });
+
+    self
   }
 
-  /// Converts the struct to an XML document.
+  /// Returns an iterator over all the outlines in the document, including
+  /// nested ones. Parent outlines are yielded before their children.
   ///
   /// # Example
   ///
   /// ```rust
-  /// use opml::OPML;
+  /// use opml::{OPML, Outline};
   ///
-  /// let opml = OPML::default();
-  /// let xml = opml.to_string().unwrap();
+  /// let mut folder = Outline {
+  ///   text: "Folder".to_string(),
+  ///   ..Outline::default()
+  /// };
+  /// folder.add_feed("Nested Feed", "https://example.com/nested");
   ///
-  /// let expected = r#"<opml version="2.0"><head/><body/></opml>"#;
-  /// assert_eq!(xml, expected);
+  /// let mut opml = OPML::default();
+  /// opml.body.outlines.push(folder);
+  /// opml.add_feed("Feed", "https://example.com/");
+  ///
+  /// let texts = opml.outlines().map(|outline| &outline.text).collect::<Vec<_>>();
+  /// assert_eq!(texts, vec!["Folder", "Nested Feed", "Feed"]);
   /// ```
-  pub fn to_string(&self) -> Result<String, Error> {
-    Ok(XmlWrite::to_string(self)?)
+  pub fn outlines(&self) -> Outlines<'_> {
+    Outlines::new(&self.body.outlines)
   }
 
-  /// Converts the struct to an XML document and writes it using the writer.
+  /// Returns an iterator over all the feeds in the document, which are the
+  /// outlines that have an `xml_url`, including nested ones.
   ///
   /// # Example
   ///
-  /// ```rust,no_run
-  /// use opml::OPML;
+  /// ```rust
+  /// use opml::{OPML, Outline};
   ///
-  /// let opml = OPML::default();
-  /// let mut file = std::fs::File::create("file.opml").unwrap();
-  /// let xml = opml.to_writer(&mut file).unwrap();
+  /// let mut folder = Outline {
+  ///   text: "Folder".to_string(),
+  ///   ..Outline::default()
+  /// };
+  /// folder.add_feed("Nested Feed", "https://example.com/nested");
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.body.outlines.push(folder);
+  /// opml.add_feed("Feed", "https://example.com/");
+  ///
+  /// let texts = opml.feeds().map(|feed| &feed.text).collect::<Vec<_>>();
+  /// assert_eq!(texts, vec!["Nested Feed", "Feed"]);
   /// ```
-  pub fn to_writer<W>(&self, writer: &mut W) -> Result<(), Error>
-  where
-    W: std::io::Write,
-  {
-    let xml_string = self.to_string()?;
-    writer.write_all(xml_string.as_bytes())?;
-    Ok(())
+  pub fn feeds(&self) -> impl Iterator<Item = &Outline> {
+    self.outlines().filter(|outline| outline.xml_url.is_some())
   }
-}
 
-impl Default for OPML {
-  fn default() -> Self {
-    OPML {
-      version: "2.0".to_string(),
-      head: Some(Head::default()),
-      body: Body::default(),
+  /// Counts the feeds (outlines with an `xml_url`) at any depth of every
+  /// top-level folder (see [`Outline::is_folder`]) and the top-level feeds
+  /// that aren't in a folder. Folders without any feeds are included with a
+  /// count of 0, other top-level outlines aren't counted.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut opml = OPML::default();
+  /// opml
+  ///   .body
+  ///   .push_folder("News")
+  ///   .add_feed("First", "https://example.com/1")
+  ///   .add_feed("Second", "https://example.com/2");
+  /// opml.add_feed("Loose Feed", "https://example.com/loose");
+  ///
+  /// let summary = opml.top_level_summary();
+  /// assert_eq!(summary.folders, vec![("News".to_string(), 2)]);
+  /// assert_eq!(summary.loose, 1);
+  /// ```
+  pub fn top_level_summary(&self) -> TopLevelSummary {
+    let mut summary = TopLevelSummary::default();
+
+    for outline in &self.body.outlines {
+      if outline.is_folder() {
+        let feeds = outline.descendant_feed_count();
+        summary.folders.push((outline.text.clone(), feeds));
+      } else if outline.xml_url.is_some() {
+        summary.loose += 1;
+      }
     }
+
+    summary
   }
-}
 
-/// The [`Head`] child element of [`OPML`]. Contains the metadata of the OPML
-/// document.
-#[derive(
-  XmlWrite,
-  XmlRead,
-  PartialEq,
-  Eq,
-  Debug,
-  Clone,
-  Default,
-  Serialize,
-  Deserialize,
-)]
-#[xml(tag = "head")]
-pub struct Head {
-  /// The title of the document.
-  #[xml(flatten_text = "title")]
-  pub title: Option<String>,
+  /// Groups all outlines in the document, including nested ones, by their
+  /// `type` attribute. Outlines without a `type` are grouped under [`None`].
+  /// Within a group the outlines are in document order.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{OPML, Outline};
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.add_feed("Untyped", "https://example.com/untyped.xml");
+  /// opml.body.outlines.push(Outline {
+  ///   text: "Rust Blog".to_string(),
+  ///   r#type: Some("rss".to_string()),
+  ///   xml_url: Some("https://blog.rust-lang.org/feed.xml".to_string()),
+  ///   ..Outline::default()
+  /// });
+  ///
+  /// let groups = opml.partition_by_type();
+  /// assert_eq!(groups[&Some("rss".to_string())][0].text, "Rust Blog");
+  /// assert_eq!(groups[&None][0].text, "Untyped");
+  /// ```
+  pub fn partition_by_type(&self) -> HashMap<Option<String>, Vec<&Outline>> {
+    let mut groups: HashMap<_, Vec<_>> = HashMap::new();
 
-  /// A date-time (RFC822) indicating when the document was created.
-  #[xml(flatten_text = "dateCreated")]
-  pub date_created: Option<String>,
+    for outline in self.outlines() {
+      groups
+        .entry(outline.r#type.clone())
+        .or_default()
+        .push(outline);
+    }
 
-  /// A date-time (RFC822) indicating when the document was last modified.
-  #[xml(flatten_text = "dateModified")]
-  pub date_modified: Option<String>,
+    groups
+  }
 
-  /// The name of the document owner.
-  #[xml(flatten_text = "ownerName")]
-  pub owner_name: Option<String>,
+  /// Returns the name and `xml_url` of every feed in the document, including
+  /// nested ones. The name is the feed's [`Outline::display_name`].
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml");
+  ///
+  /// assert_eq!(
+  ///   opml.feed_pairs(),
+  ///   vec![(
+  ///     "Rust Blog".to_string(),
+  ///     "https://blog.rust-lang.org/feed.xml".to_string()
+  ///   )]
+  /// );
+  /// ```
+  pub fn feed_pairs(&self) -> Vec<(String, String)> {
+    self
+      .feeds()
+      .filter_map(|feed| {
+        let xml_url = feed.xml_url.clone()?;
+        Some((feed.display_name().to_string(), xml_url))
+      })
+      .collect()
+  }
 
-  /// The email address of the document owner.
-  #[xml(flatten_text = "ownerEmail")]
-  pub owner_email: Option<String>,
+  /// Returns an iterator over mutable references to all the feeds in the
+  /// document, like [`OPML::feeds`].
+  ///
+  /// Outlines nested inside a feed aren't yielded, since the feed itself is
+  /// already borrowed. Use [`OPML::map_outlines_mut`] to reach those.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.add_feed("Feed", "https://example.com/");
+  ///
+  /// for feed in opml.feeds_mut() {
+  ///   feed.r#type = Some("rss".to_string());
+  /// }
+  ///
+  /// assert_eq!(opml.body.outlines[0].r#type.as_deref(), Some("rss"));
+  /// ```
+  pub fn feeds_mut(&mut self) -> FeedsMut<'_> {
+    FeedsMut::new(&mut self.body.outlines)
+  }
 
-  /// A link to the website of the document owner.
-  #[xml(flatten_text = "ownerId")]
-  pub owner_id: Option<String>,
+  /// Checks whether `f` returns true for any outline in the document,
+  /// including nested ones. Stops at the first outline that matches.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.add_feed("Feed Name", "https://example.com/");
+  ///
+  /// // Does any feed lack an htmlUrl?
+  /// assert!(opml.any_outline(|outline| outline.html_url.is_none()));
+  /// ```
+  pub fn any_outline<F>(&self, f: F) -> bool
+  where
+    F: FnMut(&Outline) -> bool,
+  {
+    self.outlines().any(f)
+  }
 
-  /// A link to the documentation of the OPML format used for this document.
-  #[xml(flatten_text = "docs")]
-  pub docs: Option<String>,
+  /// Checks whether `f` returns true for all outlines in the document,
+  /// including nested ones. Stops at the first outline that doesn't match and
+  /// returns true when there are no outlines.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.add_feed("Feed Name", "https://example.com/");
+  ///
+  /// // Are all feeds https?
+  /// assert!(opml.all_outlines(|outline| {
+  ///   outline.xml_url.as_deref().map_or(true, |url| url.starts_with("https://"))
+  /// }));
+  /// ```
+  pub fn all_outlines<F>(&self, f: F) -> bool
+  where
+    F: FnMut(&Outline) -> bool,
+  {
+    self.outlines().all(f)
+  }
 
-  /// A comma-separated list of line numbers that are expanded. The line numbers
-  /// in the list tell you which headlines to expand. The order is important.
-  /// For each element in the list, X, starting at the first summit, navigate
-  /// flatdown X times and expand. Repeat for each element in the list.
-  #[xml(flatten_text = "expansionState")]
-  pub expansion_state: Option<String>,
+  /// Checks whether the document is a flat list, meaning no outline has any
+  /// child outlines. Returns true when there are no outlines.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.add_feed("Feed Name", "https://example.com/");
+  /// assert!(opml.is_flat());
+  ///
+  /// opml.body.push_folder("Folder").add_feed("Nested", "https://example.org/");
+  /// assert!(!opml.is_flat());
+  /// ```
+  pub fn is_flat(&self) -> bool {
+    // Any nested outline has a parent at the top level, so only the top level
+    // has to be checked.
+    self
+      .body
+      .outlines
+      .iter()
+      .all(|outline| outline.outlines.is_empty())
+  }
 
-  /// A number indicating which line of the outline is displayed on the top line
-  /// of the window. This number is calculated with the expansion state already
-  /// applied.
-  #[xml(flatten_text = "vertScrollState")]
+  /// Finds the first [`Outline`] in the document, including nested ones,
+  /// with an `xml_url` that matches `url`.
+  ///
+  /// URLs are compared after normalizing them: surrounding whitespace and
+  /// trailing slashes are ignored and the scheme and host are compared
+  /// case-insensitively, so `HTTPS://Example.com/feed/` matches
+  /// `https://example.com/feed`.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml");
+  ///
+  /// let feed = opml.find_feed("https://Blog.Rust-Lang.org/feed.xml").unwrap();
+  /// assert_eq!(feed.text, "Rust Blog");
+  /// assert!(opml.find_feed("https://example.com/").is_none());
+  /// ```
+  pub fn find_feed(&self, url: &str) -> Option<&Outline> {
+    let url = normalize_feed_url(url);
+    find_feed(&self.body.outlines, &url)
+  }
+
+  /// Returns the outlines in `reference`, including nested ones, that have an
+  /// `xml_url` that isn't used anywhere in this document. URLs are matched
+  /// the same way as [`OPML::find_feed`].
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut subscriptions = OPML::default();
+  /// subscriptions.add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml");
+  ///
+  /// let mut recommendations = OPML::default();
+  /// recommendations
+  ///   .add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml")
+  ///   .add_feed("Inside Rust", "https://blog.rust-lang.org/inside-rust/feed.xml");
+  ///
+  /// let missing = subscriptions.missing_feeds(&recommendations);
+  /// assert_eq!(missing.len(), 1);
+  /// assert_eq!(missing[0].text, "Inside Rust");
+  /// ```
+  pub fn missing_feeds<'a>(&self, reference: &'a OPML) -> Vec<&'a Outline> {
+    let mut urls = HashSet::new();
+    collect_feed_urls(&self.body.outlines, &mut urls);
+
+    let mut missing = vec![];
+    collect_missing_feeds(&reference.body.outlines, &urls, &mut missing);
+    missing
+  }
+
+  /// Returns the outlines, including nested ones, that look like feeds but
+  /// won't import cleanly into a feed reader.
+  ///
+  /// An outline looks like a feed when its `type` is `rss` or `link`
+  /// (case-insensitive) or when it has an `xml_url`. It's incomplete when its
+  /// `text` is empty or only whitespace, or when it has no usable URL. The
+  /// URL of `link` outlines is their `url`, for all others it's their
+  /// `xml_url`, and it's usable when it's an absolute URL with a scheme and a
+  /// host, ignoring surrounding whitespace.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{Outline, OPML};
+  ///
+  /// let mut opml = OPML::default();
+  /// opml
+  ///   .add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml")
+  ///   .add_feed("", "https://example.com/feed.xml")
+  ///   .add_feed("Relative", "/feed.xml");
+  /// opml.body.outlines.push(Outline {
+  ///   text: "No URL".to_string(),
+  ///   r#type: Some("rss".to_string()),
+  ///   ..Outline::default()
+  /// });
+  ///
+  /// let incomplete = opml.incomplete_feeds();
+  /// assert_eq!(incomplete.len(), 3);
+  /// assert_eq!(incomplete[1].text, "Relative");
+  /// ```
+  pub fn incomplete_feeds(&self) -> Vec<&Outline> {
+    self
+      .outlines()
+      .filter(|outline| {
+        let kind = outline.r#type.as_deref().map(str::trim);
+        let is_link =
+          kind.is_some_and(|kind| kind.eq_ignore_ascii_case("link"));
+        let is_rss = kind.is_some_and(|kind| kind.eq_ignore_ascii_case("rss"));
+        if !is_link && !is_rss && outline.xml_url.is_none() {
+          return false;
+        }
+
+        let url = if is_link {
+          &outline.url
+        } else {
+          &outline.xml_url
+        };
+        let has_usable_url = url
+          .as_deref()
+          .is_some_and(|url| validation::is_url(url.trim()));
+        outline.text.trim().is_empty() || !has_usable_url
+      })
+      .collect()
+  }
+
+  /// Replaces the `xml_url` of every outline in the document, including nested
+  /// ones, that matches `old` with `new`. Returns how many outlines were
+  /// changed. URLs are matched the same way as [`OPML::find_feed`].
+  ///
+  /// See [`OPML::replace_url`] to also replace `html_url` and `url`.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.add_feed("Feed Name", "https://old.example.com/feed");
+  ///
+  /// let changed = opml.replace_feed_url(
+  ///   "https://old.example.com/feed",
+  ///   "https://new.example.com/feed",
+  /// );
+  ///
+  /// assert_eq!(changed, 1);
+  /// assert!(opml.find_feed("https://new.example.com/feed").is_some());
+  /// ```
+  pub fn replace_feed_url(&mut self, old: &str, new: &str) -> usize {
+    let old = normalize_feed_url(old);
+    let mut changed = 0;
+
+    self.map_outlines_mut(|outline| {
+      if replace_matching_url(&mut outline.xml_url, &old, new) {
+        changed += 1;
+      }
+    });
+
+    changed
+  }
+
+  /// Like [`OPML::replace_feed_url`] but replaces the matching `html_url` and
+  /// `url` attributes too. Returns how many outlines had at least one of them
+  /// changed.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{OPML, Outline};
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.body.outlines.push(Outline {
+  ///   text: "Link".to_string(),
+  ///   url: Some("https://old.example.com".to_string()),
+  ///   html_url: Some("https://old.example.com".to_string()),
+  ///   ..Outline::default()
+  /// });
+  ///
+  /// let changed = opml.replace_url("https://old.example.com", "https://new.example.com");
+  ///
+  /// assert_eq!(changed, 1);
+  /// let link = opml.body.outlines.first().unwrap();
+  /// assert_eq!(link.url.as_deref(), Some("https://new.example.com"));
+  /// assert_eq!(link.html_url.as_deref(), Some("https://new.example.com"));
+  /// ```
+  pub fn replace_url(&mut self, old: &str, new: &str) -> usize {
+    let old = normalize_feed_url(old);
+    let mut changed = 0;
+
+    self.map_outlines_mut(|outline| {
+      let xml_url = replace_matching_url(&mut outline.xml_url, &old, new);
+      let html_url = replace_matching_url(&mut outline.html_url, &old, new);
+      let url = replace_matching_url(&mut outline.url, &old, new);
+
+      if xml_url || html_url || url {
+        changed += 1;
+      }
+    });
+
+    changed
+  }
+
+  /// Returns the [`Outline`] at the given index path, where each index selects
+  /// a child of the previous outline, starting with the top-level outlines in
+  /// the [`Body`]. Returns [`None`] when the path is empty or out of range.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{OPML, Outline};
+  ///
+  /// let mut folder = Outline::default();
+  /// folder.add_feed("Feed Name", "https://example.com/");
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.body.outlines.push(folder);
+  ///
+  /// assert_eq!(opml.outline_at(&[0, 0]).unwrap().text, "Feed Name");
+  /// assert!(opml.outline_at(&[0, 1]).is_none());
+  /// ```
+  pub fn outline_at(&self, path: &[usize]) -> Option<&Outline> {
+    let (first, rest) = path.split_first()?;
+    let mut outline = self.body.outlines.get(*first)?;
+
+    for index in rest {
+      outline = outline.outlines.get(*index)?;
+    }
+
+    Some(outline)
+  }
+
+  /// Returns the outlines at the same level as the outline at the given index
+  /// path, including that outline itself at the path's last index. Returns
+  /// [`None`] when the path is empty or doesn't point to an outline. See
+  /// [`OPML::outline_at`] for how paths work.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{OPML, Outline};
+  ///
+  /// let mut folder = Outline::default();
+  /// folder
+  ///   .add_feed("First", "https://example.com/1")
+  ///   .add_feed("Second", "https://example.com/2");
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.body.outlines.push(folder);
+  ///
+  /// let siblings = opml.siblings(&[0, 0]).unwrap();
+  /// assert_eq!(siblings.len(), 2);
+  /// assert_eq!(siblings[1].text, "Second");
+  /// assert!(opml.siblings(&[0, 2]).is_none());
+  /// ```
+  pub fn siblings(&self, path: &[usize]) -> Option<&[Outline]> {
+    let (index, parent) = path.split_last()?;
+
+    let siblings = if parent.is_empty() {
+      &self.body.outlines
+    } else {
+      &self.outline_at(parent)?.outlines
+    };
+
+    siblings.get(*index).map(|_| siblings.as_slice())
+  }
+
+  /// Mutable version of [`OPML::outline_at`].
+  pub fn outline_at_mut(&mut self, path: &[usize]) -> Option<&mut Outline> {
+    let (first, rest) = path.split_first()?;
+    let mut outline = self.body.outlines.get_mut(*first)?;
+
+    for index in rest {
+      outline = outline.outlines.get_mut(*index)?;
+    }
+
+    Some(outline)
+  }
+
+  /// Returns the children of the outline at the given index path, or the
+  /// top-level outlines when the path is empty.
+  fn children_at_mut(&mut self, path: &[usize]) -> Option<&mut Vec<Outline>> {
+    if path.is_empty() {
+      Some(&mut self.body.outlines)
+    } else {
+      self
+        .outline_at_mut(path)
+        .map(|outline| &mut outline.outlines)
+    }
+  }
+
+  /// Moves the outline at the index path `from` to become the last child of
+  /// the outline at `to_parent`, or the last top-level outline when
+  /// `to_parent` is empty. See [`OPML::outline_at`] for how paths work.
+  ///
+  /// Returns [`Error::InvalidMove`] when either path doesn't point to an
+  /// outline or when `to_parent` is `from` itself or one of its descendants.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{OPML, Outline};
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.body.outlines.push(Outline {
+  ///   text: "Folder".to_string(),
+  ///   ..Outline::default()
+  /// });
+  /// opml.add_feed("Feed Name", "https://example.com/");
+  ///
+  /// opml.move_outline(&[1], &[0]).unwrap();
+  /// assert_eq!(opml.outline_at(&[0, 0]).unwrap().text, "Feed Name");
+  ///
+  /// assert!(opml.move_outline(&[0], &[0, 0]).is_err());
+  /// ```
+  pub fn move_outline(
+    &mut self,
+    from: &[usize],
+    to_parent: &[usize],
+  ) -> Result<(), Error> {
+    let (from_index, from_parent) = match from.split_last() {
+      Some(split) if self.outline_at(from).is_some() => split,
+      _ => {
+        return Err(Error::InvalidMove(format!(
+          "no outline to move at {from:?}"
+        )))
+      }
+    };
+
+    if !to_parent.is_empty() && self.outline_at(to_parent).is_none() {
+      return Err(Error::InvalidMove(format!(
+        "no parent outline at {to_parent:?}"
+      )));
+    }
+
+    if to_parent.starts_with(from) {
+      return Err(Error::InvalidMove(format!(
+        "can't move {from:?} into itself or its descendant {to_parent:?}"
+      )));
+    }
+
+    // Removing the outline shifts its later siblings back by one, so adjust
+    // the parent path if it goes through one of them.
+    let mut to_parent = to_parent.to_vec();
+    let depth = from_parent.len();
+    if to_parent.len() > depth
+      && to_parent.starts_with(from_parent)
+      && to_parent[depth] > *from_index
+    {
+      to_parent[depth] -= 1;
+    }
+
+    // Both paths were validated above, so these can't fail.
+    let outline = self
+      .children_at_mut(from_parent)
+      .expect("from parent path is valid")
+      .remove(*from_index);
+    self
+      .children_at_mut(&to_parent)
+      .expect("to parent path is valid")
+      .push(outline);
+
+    Ok(())
+  }
+
+  /// Adds the outline as the last child of the outline at the index path
+  /// `parent`, or as the last top-level outline when `parent` is empty. See
+  /// [`OPML::outline_at`] for how paths work.
+  ///
+  /// Returns [`Error::InvalidPath`] when `parent` doesn't point to an outline.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{OPML, Outline};
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.append_outline(&[], Outline {
+  ///   text: "Folder".to_string(),
+  ///   ..Outline::default()
+  /// }).unwrap();
+  /// opml.append_outline(&[0], Outline {
+  ///   text: "Feed Name".to_string(),
+  ///   xml_url: Some("https://example.com/".to_string()),
+  ///   ..Outline::default()
+  /// }).unwrap();
+  ///
+  /// assert_eq!(opml.outline_at(&[0, 0]).unwrap().text, "Feed Name");
+  /// assert!(opml.append_outline(&[1], Outline::default()).is_err());
+  /// ```
+  pub fn append_outline(
+    &mut self,
+    parent: &[usize],
+    outline: Outline,
+  ) -> Result<(), Error> {
+    self
+      .children_at_mut(parent)
+      .ok_or_else(|| {
+        Error::InvalidPath(format!("no parent outline at {parent:?}"))
+      })?
+      .push(outline);
+
+    Ok(())
+  }
+
+  /// Calls `f` on every [`Outline`] in the document, including nested ones.
+  /// Parent outlines are visited before their children.
+  ///
+  /// Since children are visited after `f` has been called on their parent,
+  /// any changes `f` makes to a parent's `outlines` affect the traversal:
+  /// added children will be visited and removed children will not.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.add_feed("Feed Name", "http://example.com/");
+  ///
+  /// opml.map_outlines_mut(|outline| {
+  ///   if let Some(url) = &outline.xml_url {
+  ///     outline.xml_url = Some(url.replacen("http://", "https://", 1));
+  ///   }
+  /// });
+  ///
+  /// let feed = opml.body.outlines.first().unwrap();
+  /// assert_eq!(feed.xml_url.as_deref(), Some("https://example.com/"));
+  /// ```
+  pub fn map_outlines_mut<F>(&mut self, mut f: F)
+  where
+    F: FnMut(&mut Outline),
+  {
+    map_outlines_mut(&mut self.body.outlines, &mut f);
+  }
+
+  /// Sorts all outlines so the document is written the same way regardless of
+  /// the order the outlines were added in, useful for reproducible exports.
+  ///
+  /// Siblings are sorted with folders (see [`Outline::is_folder`]) first, then
+  /// by `text`, then by `xml_url` and finally by their written XML, which
+  /// includes all other attributes and their sorted children. Outlines that
+  /// are equal in all of these are written the same way, so the order they
+  /// end up in doesn't matter, and running this more than once doesn't change
+  /// the result. The [`Head`] fields are always written in the same order so
+  /// they don't need any changes.
+  ///
+  /// Note that the `expansion_state` and `vert_scroll_state` in the [`Head`]
+  /// refer to line numbers, which won't match the sorted outlines anymore.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut opml = OPML::default();
+  /// opml
+  ///   .add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml")
+  ///   .add_feed("Inside Rust", "https://blog.rust-lang.org/inside-rust/feed.xml");
+  ///
+  /// opml.canonicalize();
+  /// assert_eq!(opml.body.outlines[0].text, "Inside Rust");
+  /// ```
+  pub fn canonicalize(&mut self) {
+    canonicalize(&mut self.body.outlines);
+  }
+
+  /// Returns a new document containing only the outlines with a category that
+  /// contains `category`, with the same version and [`Head`].
+  ///
+  /// Both the outline categories and `category` are split into their
+  /// slash-delimited segments (see [`Outline::categories`]) and an outline
+  /// matches when its segments contain all of the segments of `category` in
+  /// order, so `/News` matches `/News/Tech` and `Tech` matches it too.
+  ///
+  /// Matching outlines are kept together with all of their children. The
+  /// ancestor folders of matching outlines are kept as well, but only with
+  /// their children that (contain outlines that) match.
+  ///
+  /// When nothing matches the returned document has an empty body, note that
+  /// the spec requires at least one outline so check for this before writing
+  /// the document if it should be parsed again, or use
+  /// [`OPML::try_filter_by_category`] to get an error instead.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{OPML, Outline};
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.body.outlines.push(Outline {
+  ///   text: "Rust Blog".to_string(),
+  ///   category: Some("/News/Tech,/Blogs".to_string()),
+  ///   ..Outline::default()
+  /// });
+  /// opml.body.outlines.push(Outline {
+  ///   text: "Cooking".to_string(),
+  ///   category: Some("/Hobbies".to_string()),
+  ///   ..Outline::default()
+  /// });
+  ///
+  /// let news = opml.filter_by_category("/News");
+  /// assert_eq!(news.body.outlines.len(), 1);
+  /// assert_eq!(news.body.outlines[0].text, "Rust Blog");
+  /// ```
+  pub fn filter_by_category(&self, category: &str) -> OPML {
+    let query = category_segments(category)
+      .into_iter()
+      .filter(|segment| !segment.is_empty())
+      .collect::<Vec<_>>();
+
+    let outlines = if query.is_empty() {
+      vec![]
+    } else {
+      filter_by_category(&self.body.outlines, &query)
+    };
+
+    self.sub_document(outlines)
+  }
+
+  /// Like [`OPML::filter_by_category`], but returns
+  /// [`Error::BodyHasNoOutlines`] when nothing matches, so the result is
+  /// always a document that can be written and parsed again.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{Error, OPML, Outline};
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.body.outlines.push(Outline {
+  ///   text: "Rust Blog".to_string(),
+  ///   category: Some("/News/Tech".to_string()),
+  ///   ..Outline::default()
+  /// });
+  ///
+  /// assert!(opml.try_filter_by_category("/News").is_ok());
+  /// assert!(matches!(
+  ///   opml.try_filter_by_category("/Hobbies"),
+  ///   Err(Error::BodyHasNoOutlines)
+  /// ));
+  /// ```
+  pub fn try_filter_by_category(&self, category: &str) -> Result<OPML, Error> {
+    let filtered = self.filter_by_category(category);
+
+    if filtered.body.outlines.is_empty() {
+      return Err(Error::BodyHasNoOutlines);
+    }
+
+    Ok(filtered)
+  }
+
+  /// Creates a new document containing the contents of the first folder
+  /// (see [`Outline::is_folder`]) whose `text` is `folder_text`, searching
+  /// nested folders too. The folder's children become the top-level outlines
+  /// of the new document, keeping their own nesting. The version and [`Head`]
+  /// are copied from this document.
+  ///
+  /// Returns [`Error::FolderNotFound`] when no outline has the text, and
+  /// [`Error::NotAFolder`] when the outlines that have it are feeds or empty
+  /// folders without children.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{OPML, Outline};
+  ///
+  /// let mut tech = Outline {
+  ///   text: "Tech".to_string(),
+  ///   ..Outline::default()
+  /// };
+  /// tech.add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml");
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.body.outlines.push(tech);
+  /// opml.add_feed("News", "https://example.com/news.xml");
+  ///
+  /// let exported = opml.export_folder("Tech").unwrap();
+  /// assert_eq!(exported.body.outlines.len(), 1);
+  /// assert_eq!(exported.body.outlines[0].text, "Rust Blog");
+  ///
+  /// assert!(opml.export_folder("Sports").is_err());
+  /// ```
+  pub fn export_folder(&self, folder_text: &str) -> Result<OPML, Error> {
+    let mut found = false;
+
+    for outline in self.outlines() {
+      if outline.text != folder_text {
+        continue;
+      }
+
+      if outline.is_folder() {
+        return Ok(self.sub_document(outline.outlines.clone()));
+      }
+
+      found = true;
+    }
+
+    if found {
+      Err(Error::NotAFolder(folder_text.to_string()))
+    } else {
+      Err(Error::FolderNotFound(folder_text.to_string()))
+    }
+  }
+
+  /// Removes every feed (an outline with an `xml_url`) for which `keep`
+  /// returns false, along with its children, and then removes the folders
+  /// that were left without any children. Returns the amount of feeds that
+  /// were removed.
+  ///
+  /// Folders aren't passed to `keep`, they are only removed when all their
+  /// children were. Outlines without an `xml_url` that didn't have any
+  /// children to begin with are left alone.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{OPML, Outline};
+  ///
+  /// let mut folder = Outline {
+  ///   text: "German".to_string(),
+  ///   ..Outline::default()
+  /// };
+  /// folder.outlines.push(Outline {
+  ///   text: "Golem".to_string(),
+  ///   xml_url: Some("https://rss.golem.de/rss.php".to_string()),
+  ///   language: Some("de".to_string()),
+  ///   ..Outline::default()
+  /// });
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.body.outlines.push(folder);
+  /// opml.body.outlines.push(Outline {
+  ///   text: "Rust Blog".to_string(),
+  ///   xml_url: Some("https://blog.rust-lang.org/feed.xml".to_string()),
+  ///   language: Some("en".to_string()),
+  ///   ..Outline::default()
+  /// });
+  ///
+  /// let removed = opml.retain_feeds(|feed| feed.language.as_deref() == Some("en"));
+  /// assert_eq!(removed, 1);
+  /// assert_eq!(opml.body.outlines.len(), 1);
+  /// assert_eq!(opml.body.outlines[0].text, "Rust Blog");
+  /// ```
+  pub fn retain_feeds<F>(&mut self, keep: F) -> usize
+  where
+    F: Fn(&Outline) -> bool,
+  {
+    retain_feeds(&mut self.body.outlines, &keep)
+  }
+
+  /// Returns the outlines that the [`Head`]'s `expansion_state` says should
+  /// be expanded, in the order they appear in the expansion state.
+  ///
+  /// The expansion state is a comma-separated list of line numbers. Lines are
+  /// numbered starting at 1 for the first top-level outline and only count
+  /// visible outlines, which are the top-level outlines and the children of
+  /// outlines that have already been expanded. For each number in the list,
+  /// the outline on that line is expanded, revealing its children for the
+  /// numbers that come after it.
+  ///
+  /// For example, with `1, 2` the first top-level outline is expanded, which
+  /// makes its first child line 2, so that child is expanded next.
+  ///
+  /// Numbers that can't be parsed, that are 0, or that are past the last
+  /// visible line are ignored.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{Head, OPML, Outline};
+  ///
+  /// let mut folder = Outline {
+  ///   text: "Folder".to_string(),
+  ///   ..Outline::default()
+  /// };
+  /// folder.outlines.push(Outline {
+  ///   text: "Subfolder".to_string(),
+  ///   ..Outline::default()
+  /// });
+  ///
+  /// let mut opml = OPML::default();
+  /// opml.body.outlines.push(folder);
+  /// opml.head = Some(Head {
+  ///   expansion_state: Some("1, 2".to_string()),
+  ///   ..Head::default()
+  /// });
+  ///
+  /// let expanded = opml.expanded_outlines();
+  /// assert_eq!(expanded[0].text, "Folder");
+  /// assert_eq!(expanded[1].text, "Subfolder");
+  /// ```
+  pub fn expanded_outlines(&self) -> Vec<&Outline> {
+    let expansion_state = match &self.head {
+      Some(Head {
+        expansion_state: Some(expansion_state),
+        ..
+      }) => expansion_state,
+      _ => return vec![],
+    };
+
+    let mut expanded: Vec<&Outline> = vec![];
+
+    for line in expansion_state.split(',') {
+      let mut remaining = match line.trim().parse::<usize>() {
+        Ok(line) if line > 0 => line,
+        _ => continue,
+      };
+
+      let outline =
+        find_visible_line(&self.body.outlines, &expanded, &mut remaining);
+
+      if let Some(outline) = outline {
+        if !is_expanded(&expanded, outline) {
+          expanded.push(outline);
+        }
+      }
+    }
+
+    expanded
+  }
+
+  /// Creates a new document with the same version and [`Head`] as this one,
+  /// containing the given outlines.
+  fn sub_document(&self, outlines: Vec<Outline>) -> OPML {
+    OPML {
+      version: self.version.clone(),
+      head: self.head.clone(),
+      body: Body { outlines },
+      source_encoding: self.source_encoding.clone(),
+    }
+  }
+
+  /// Deprecated, use [`OPML::to_string`] instead.
+  #[deprecated(note = "Use to_string instead", since = "1.1.0")]
+  pub fn to_xml(&self) -> Result<String, Error> {
+    self.to_string()
+  }
+
+  /// Converts the struct to an XML document.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let opml = OPML::default();
+  /// let xml = opml.to_string().unwrap();
+  ///
+  /// let expected = r#"<opml version="2.0"><head/><body/></opml>"#;
+  /// assert_eq!(xml, expected);
+  /// ```
+  pub fn to_string(&self) -> Result<String, Error> {
+    self.to_string_with_options(&WriteOptions::default())
+  }
+
+  /// Converts the struct to an XML document using the given [`WriteOptions`].
+  /// The document itself isn't changed.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{Head, OPML, WriteOptions};
+  ///
+  /// let opml = OPML {
+  ///   head: Some(Head {
+  ///     title: Some("Feeds".to_string()),
+  ///     owner_email: Some("owner@example.com".to_string()),
+  ///     ..Head::default()
+  ///   }),
+  ///   ..OPML::default()
+  /// };
+  /// let xml = opml.to_string_with_options(&WriteOptions::redact_owner()).unwrap();
+  ///
+  /// let expected = r#"<opml version="2.0"><head><title>Feeds</title></head><body/></opml>"#;
+  /// assert_eq!(xml, expected);
+  /// ```
+  pub fn to_string_with_options(
+    &self,
+    options: &WriteOptions,
+  ) -> Result<String, Error> {
+    let mut xml = String::new();
+    write::write_opml_fmt(self, &mut xml, options)?;
+    Ok(xml)
+  }
+
+  /// Converts the struct to an XML document as UTF-8 bytes.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let opml = OPML::default();
+  /// let bytes = opml.to_bytes().unwrap();
+  ///
+  /// assert_eq!(bytes, br#"<opml version="2.0"><head/><body/></opml>"#);
+  /// ```
+  pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+    self.to_bytes_with_options(&WriteOptions::default())
+  }
+
+  /// Converts the struct to an XML document as UTF-8 bytes using the given
+  /// [`WriteOptions`].
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{OPML, WriteOptions};
+  ///
+  /// let options = WriteOptions {
+  ///   byte_order_mark: true,
+  ///   xml_declaration: true,
+  ///   ..WriteOptions::default()
+  /// };
+  /// let bytes = OPML::default().to_bytes_with_options(&options).unwrap();
+  ///
+  /// assert!(bytes.starts_with(b"\xEF\xBB\xBF<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+  /// ```
+  pub fn to_bytes_with_options(
+    &self,
+    options: &WriteOptions,
+  ) -> Result<Vec<u8>, Error> {
+    let mut writer = XmlWriter::new(vec![]);
+    write::write_opml(self, &mut writer, options)?;
+    Ok(writer.inner)
+  }
+
+  /// Converts the struct to an XML document and writes it into any
+  /// [`std::fmt::Write`] sink, like a [`String`] or a [`std::fmt::Formatter`],
+  /// without allocating the document as a separate string first.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let opml = OPML::default();
+  /// let mut page = String::from("<pre>");
+  /// opml.write_to(&mut page).unwrap();
+  ///
+  /// assert_eq!(page, r#"<pre><opml version="2.0"><head/><body/></opml>"#);
+  /// ```
+  pub fn write_to<W>(&self, writer: &mut W) -> Result<(), Error>
+  where
+    W: std::fmt::Write,
+  {
+    write::write_opml_fmt(self, writer, &WriteOptions::default())
+  }
+
+  /// Converts the struct to an XML document and writes it using the writer.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// use opml::OPML;
+  ///
+  /// let opml = OPML::default();
+  /// let mut file = std::fs::File::create("file.opml").unwrap();
+  /// let xml = opml.to_writer(&mut file).unwrap();
+  /// ```
+  pub fn to_writer<W>(&self, writer: &mut W) -> Result<(), Error>
+  where
+    W: std::io::Write,
+  {
+    let xml_string = self.to_string()?;
+    writer.write_all(xml_string.as_bytes())?;
+    Ok(())
+  }
+
+  /// Converts the struct to an XML document using the given [`WriteOptions`]
+  /// and writes it using the writer.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// use opml::{OPML, WriteOptions};
+  ///
+  /// let opml = OPML::default();
+  /// let mut file = std::fs::File::create("file.opml").unwrap();
+  /// opml.to_writer_with_options(&mut file, &WriteOptions::redact_owner()).unwrap();
+  /// ```
+  pub fn to_writer_with_options<W>(
+    &self,
+    writer: &mut W,
+    options: &WriteOptions,
+  ) -> Result<(), Error>
+  where
+    W: std::io::Write,
+  {
+    let xml_string = self.to_string_with_options(options)?;
+    writer.write_all(xml_string.as_bytes())?;
+    Ok(())
+  }
+
+  /// Sets the [`Head`]'s `date_modified` to the current date-time (RFC822) and
+  /// then writes the document like [`OPML::to_writer`].
+  ///
+  /// Note that this mutates the document, creating a [`Head`] if there isn't
+  /// one already. Use [`OPML::to_writer`] to write without any changes.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut opml = OPML::default();
+  /// let mut writer = vec![];
+  /// opml.to_writer_touching(&mut writer).unwrap();
+  ///
+  /// let head = opml.head.unwrap();
+  /// assert!(head.date_modified.unwrap().ends_with(" GMT"));
+  /// ```
+  #[cfg(feature = "date")]
+  pub fn to_writer_touching<W>(&mut self, writer: &mut W) -> Result<(), Error>
+  where
+    W: std::io::Write,
+  {
+    let head = self.head.get_or_insert_with(Head::default);
+    head.date_modified = Some(date::now_rfc822());
+    self.to_writer(writer)
+  }
+}
+
+/// Options for parsing documents with [`OPML::from_str_with_options`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+  /// Match the attribute names of [`Outline`] elements case-insensitively,
+  /// so non-conforming but common attributes like `xmlurl` and `htmlurl` are
+  /// parsed as `xmlUrl` and `htmlUrl`. This applies to all [`Outline`]
+  /// attributes, when an attribute appears more than once with different
+  /// casing the last one is used.
+  ///
+  /// By default attribute names are matched exactly, as per the spec.
+  pub case_insensitive_attributes: bool,
+
+  /// The maximum amount of outlines a document may contain, counting nested
+  /// outlines at any depth. Parsing stops with [`Error::TooManyOutlines`] as
+  /// soon as the limit is exceeded, which protects against documents with
+  /// millions of outlines.
+  ///
+  /// By default there is no limit.
+  pub max_outlines: Option<usize>,
+
+  /// Record the index every outline has among its siblings as its
+  /// `original_index`, so the original order can be restored after the
+  /// outlines have been reordered.
+  ///
+  /// The index is ignored when comparing outlines, so parsed outlines are
+  /// still equal to the same outlines built in code. By default the index
+  /// isn't recorded.
+  pub record_original_index: bool,
+}
+
+/// Options for writing documents with [`OPML::to_string_with_options`] and
+/// [`OPML::to_writer_with_options`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+  /// Start the document with a UTF-8 byte order mark (`U+FEFF`), for
+  /// programs that rely on it to detect the encoding.
+  ///
+  /// By default no byte order mark is written.
+  pub byte_order_mark: bool,
+
+  /// The encoding to declare in the XML declaration written with
+  /// `xml_declaration`, like the document's `source_encoding` to declare the
+  /// encoding it was parsed from. The written XML is always UTF-8, so the
+  /// caller is responsible for transcoding the output to this encoding.
+  ///
+  /// By default `UTF-8` is declared.
+  pub declared_encoding: Option<String>,
+
+  /// The [`Head`] fields to leave out of the written document, even when they
+  /// are set. This only affects the output, the document isn't changed.
+  pub omit_head_fields: Vec<HeadField>,
+
+  /// Leave out the [`Head`]'s `owner_email` when it isn't an email address
+  /// and its `owner_id` when it isn't a URL, including when they are empty.
+  /// With the `tracing` feature, a debug event is recorded for every field
+  /// that is left out.
+  ///
+  /// By default all fields are written as they are.
+  pub omit_invalid_head_fields: bool,
+
+  /// Quote attribute values with single quotes (`text='...'`) instead of
+  /// double quotes, for parsers that only accept single quotes. Quotes inside
+  /// values are escaped as `&apos;` and `&quot;` in both styles.
+  ///
+  /// By default attribute values are double-quoted.
+  pub single_quote_attributes: bool,
+
+  /// Write an XML declaration before the document, like
+  /// `<?xml version="1.0" encoding="UTF-8"?>`. The written XML is always
+  /// UTF-8, so that's the declared encoding regardless of the document's
+  /// `source_encoding`, unless `declared_encoding` is set.
+  pub xml_declaration: bool,
+}
+
+impl WriteOptions {
+  /// Options that leave out the owner's name, email and ID, for sharing
+  /// documents without personal information.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{HeadField, WriteOptions};
+  ///
+  /// let options = WriteOptions::redact_owner();
+  /// assert!(options.omit_head_fields.contains(&HeadField::OwnerEmail));
+  /// ```
+  pub fn redact_owner() -> Self {
+    WriteOptions {
+      omit_head_fields: vec![
+        HeadField::OwnerName,
+        HeadField::OwnerEmail,
+        HeadField::OwnerId,
+      ],
+      ..WriteOptions::default()
+    }
+  }
+}
+
+/// The fields of the [`Head`], used to select fields in [`WriteOptions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HeadField {
+  /// The [`Head::title`] field.
+  Title,
+  /// The [`Head::date_created`] field.
+  DateCreated,
+  /// The [`Head::date_modified`] field.
+  DateModified,
+  /// The [`Head::owner_name`] field.
+  OwnerName,
+  /// The [`Head::owner_email`] field.
+  OwnerEmail,
+  /// The [`Head::owner_id`] field.
+  OwnerId,
+  /// The [`Head::docs`] field.
+  Docs,
+  /// The [`Head::expansion_state`] field.
+  ExpansionState,
+  /// The [`Head::vert_scroll_state`] field.
+  VertScrollState,
+  /// The [`Head::window_top`] field.
+  WindowTop,
+  /// The [`Head::window_left`] field.
+  WindowLeft,
+  /// The [`Head::window_bottom`] field.
+  WindowBottom,
+  /// The [`Head::window_right`] field.
+  WindowRight,
+}
+
+impl Default for OPML {
+  fn default() -> Self {
+    OPML {
+      version: "2.0".to_string(),
+      head: Some(Head::default()),
+      body: Body::default(),
+      source_encoding: None,
+    }
+  }
+}
+
+impl PartialEq for OPML {
+  fn eq(&self, other: &Self) -> bool {
+    let OPML {
+      version,
+      head,
+      body,
+      // Where the document was parsed from doesn't change its content.
+      source_encoding: _,
+    } = self;
+
+    *version == other.version && *head == other.head && *body == other.body
+  }
+}
+
+/// The feed counts of a document's top-level outlines, created with
+/// [`OPML::top_level_summary`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TopLevelSummary {
+  /// The `text` of every top-level folder and the amount of feeds it contains
+  /// at any depth, in document order.
+  pub folders: Vec<(String, usize)>,
+
+  /// The amount of top-level feeds that aren't in a folder.
+  pub loose: usize,
+}
+
+/// The [`Head`] child element of [`OPML`]. Contains the metadata of the OPML
+/// document.
+#[derive(
+  XmlWrite, PartialEq, Eq, Debug, Clone, Default, Serialize, Deserialize,
+)]
+#[xml(tag = "head")]
+#[cfg_attr(feature = "serde-camel-case", serde(rename_all = "camelCase"))]
+pub struct Head {
+  /// The title of the document.
+  #[xml(flatten_text = "title")]
+  pub title: Option<String>,
+
+  /// A date-time (RFC822) indicating when the document was created.
+  #[xml(flatten_text = "dateCreated")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "date_created"))]
+  pub date_created: Option<String>,
+
+  /// A date-time (RFC822) indicating when the document was last modified.
+  #[xml(flatten_text = "dateModified")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "date_modified"))]
+  pub date_modified: Option<String>,
+
+  /// The name of the document owner.
+  #[xml(flatten_text = "ownerName")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "owner_name"))]
+  pub owner_name: Option<String>,
+
+  /// The email address of the document owner.
+  #[xml(flatten_text = "ownerEmail")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "owner_email"))]
+  pub owner_email: Option<String>,
+
+  /// A link to the website of the document owner.
+  #[xml(flatten_text = "ownerId")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "owner_id"))]
+  pub owner_id: Option<String>,
+
+  /// A link to the documentation of the OPML format used for this document.
+  #[xml(flatten_text = "docs")]
+  pub docs: Option<String>,
+
+  /// A comma-separated list of line numbers that are expanded. The line numbers
+  /// in the list tell you which headlines to expand. The order is important.
+  /// For each element in the list, X, starting at the first summit, navigate
+  /// flatdown X times and expand. Repeat for each element in the list.
+  #[xml(flatten_text = "expansionState")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "expansion_state"))]
+  pub expansion_state: Option<String>,
+
+  /// A number indicating which line of the outline is displayed on the top line
+  /// of the window. This number is calculated with the expansion state already
+  /// applied.
+  ///
+  /// This and the window fields below are parsed as absent when their element
+  /// is empty, like `<windowTop/>`.
+  #[xml(flatten_text = "vertScrollState")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "vert_scroll_state"))]
+  pub vert_scroll_state: Option<i32>,
+
+  /// The pixel location of the top edge of the window.
+  #[xml(flatten_text = "windowTop")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "window_top"))]
+  pub window_top: Option<i32>,
+
+  /// The pixel location of the left edge of the window.
+  #[xml(flatten_text = "windowLeft")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "window_left"))]
+  pub window_left: Option<i32>,
+
+  /// The pixel location of the bottom edge of the window.
+  #[xml(flatten_text = "windowBottom")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "window_bottom"))]
+  pub window_bottom: Option<i32>,
+
+  /// The pixel location of the right edge of the window.
+  #[xml(flatten_text = "windowRight")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "window_right"))]
+  pub window_right: Option<i32>,
+}
+
+impl Head {
+  /// Returns the link to the documentation of the OPML format, or [`None`]
+  /// when `docs` is absent or empty. An empty `docs` element is still parsed
+  /// as `Some("")` so the document is written the same way again.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::Head;
+  ///
+  /// let mut head = Head {
+  ///   docs: Some("http://opml.org/spec2.opml".to_string()),
+  ///   ..Head::default()
+  /// };
+  /// assert_eq!(head.docs_url(), Some("http://opml.org/spec2.opml"));
+  ///
+  /// head.docs = Some("".to_string());
+  /// assert_eq!(head.docs_url(), None);
+  /// ```
+  pub fn docs_url(&self) -> Option<&str> {
+    self
+      .docs
+      .as_deref()
+      .map(str::trim)
+      .filter(|docs| !docs.is_empty())
+  }
+
+  /// Groups the fields describing the state of the window the document was
+  /// last displayed in, or returns [`None`] when none of them are set.
+  ///
+  /// The flat fields remain the source of truth and are what gets written,
+  /// use [`Head::set_window_state`] to update them from a [`WindowState`].
+  /// Numbers in the `expansion_state` that can't be parsed are left out.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::Head;
+  ///
+  /// let mut head = Head::default();
+  /// assert_eq!(head.window_state(), None);
+  ///
+  /// head.expansion_state = Some("1, 3".to_string());
+  /// head.window_top = Some(100);
+  ///
+  /// let window_state = head.window_state().unwrap();
+  /// assert_eq!(window_state.expansion_state, vec![1, 3]);
+  /// assert_eq!(window_state.top, Some(100));
+  /// ```
+  pub fn window_state(&self) -> Option<WindowState> {
+    if self.expansion_state.is_none()
+      && self.vert_scroll_state.is_none()
+      && self.window_top.is_none()
+      && self.window_left.is_none()
+      && self.window_bottom.is_none()
+      && self.window_right.is_none()
+    {
+      return None;
+    }
+
+    let expansion_state = self
+      .expansion_state
+      .iter()
+      .flat_map(|expansion_state| expansion_state.split(','))
+      .filter_map(|line| line.trim().parse().ok())
+      .collect();
+
+    Some(WindowState {
+      expansion_state,
+      vert_scroll_state: self.vert_scroll_state,
+      top: self.window_top,
+      left: self.window_left,
+      bottom: self.window_bottom,
+      right: self.window_right,
+    })
+  }
+
+  /// Sets the flat window fields from a [`WindowState`]. An empty
+  /// `expansion_state` removes the field.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{Head, WindowState};
+  ///
+  /// let mut head = Head::default();
+  /// head.set_window_state(WindowState {
+  ///   expansion_state: vec![1, 3],
+  ///   right: Some(800),
+  ///   ..WindowState::default()
+  /// });
+  ///
+  /// assert_eq!(head.expansion_state.as_deref(), Some("1,3"));
+  /// assert_eq!(head.window_right, Some(800));
+  /// ```
+  pub fn set_window_state(&mut self, window_state: WindowState) {
+    self.expansion_state = if window_state.expansion_state.is_empty() {
+      None
+    } else {
+      let lines: Vec<String> = window_state
+        .expansion_state
+        .iter()
+        .map(usize::to_string)
+        .collect();
+      Some(lines.join(","))
+    };
+
+    self.vert_scroll_state = window_state.vert_scroll_state;
+    self.window_top = window_state.top;
+    self.window_left = window_state.left;
+    self.window_bottom = window_state.bottom;
+    self.window_right = window_state.right;
+  }
+}
+
+/// The state of the window a document was last displayed in, created with
+/// [`Head::window_state`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WindowState {
+  /// The line numbers that are expanded, see [`Head::expansion_state`].
+  pub expansion_state: Vec<usize>,
+
+  /// The line displayed at the top of the window, see
+  /// [`Head::vert_scroll_state`].
   pub vert_scroll_state: Option<i32>,
 
   /// The pixel location of the top edge of the window.
-  #[xml(flatten_text = "windowTop")]
-  pub window_top: Option<i32>,
+  pub top: Option<i32>,
 
   /// The pixel location of the left edge of the window.
-  #[xml(flatten_text = "windowLeft")]
-  pub window_left: Option<i32>,
+  pub left: Option<i32>,
 
   /// The pixel location of the bottom edge of the window.
-  #[xml(flatten_text = "windowBottom")]
-  pub window_bottom: Option<i32>,
+  pub bottom: Option<i32>,
 
   /// The pixel location of the right edge of the window.
-  #[xml(flatten_text = "windowRight")]
-  pub window_right: Option<i32>,
+  pub right: Option<i32>,
 }
 
 /// The [`Body`] child element of [`OPML`]. Contains all the [`Outline`]
@@ -320,19 +1973,162 @@ pub struct Body {
   pub outlines: Vec<Outline>,
 }
 
+impl Body {
+  /// Compares two bodies while ignoring the order of sibling outlines.
+  ///
+  /// The nesting structure still has to match: two outlines are only equal
+  /// when all their attributes are equal and their children are equal, again
+  /// ignoring order. Duplicates are counted, so a body with the same outline
+  /// twice isn't equal to a body that has it once.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::OPML;
+  ///
+  /// let mut first = OPML::default();
+  /// first
+  ///   .add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml")
+  ///   .add_feed("Inside Rust", "https://blog.rust-lang.org/inside-rust/feed.xml");
+  ///
+  /// let mut second = OPML::default();
+  /// second
+  ///   .add_feed("Inside Rust", "https://blog.rust-lang.org/inside-rust/feed.xml")
+  ///   .add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml");
+  ///
+  /// assert_ne!(first.body, second.body);
+  /// assert!(first.body.eq_unordered(&second.body));
+  /// ```
+  pub fn eq_unordered(&self, other: &Body) -> bool {
+    outlines_eq_unordered(&self.outlines, &other.outlines)
+  }
+
+  /// Adds an [`Outline`] element with `text` and `xml_url` attributes as a
+  /// top-level outline and returns it, like [`OPML::add_feed`] but for
+  /// further changes to the added feed.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::Body;
+  ///
+  /// let mut body = Body::default();
+  /// body.push_feed("Feed Name", "https://example.com/").r#type =
+  ///   Some("rss".to_string());
+  ///
+  /// assert_eq!(body.outlines[0].r#type.as_deref(), Some("rss"));
+  /// ```
+  pub fn push_feed(&mut self, text: &str, url: &str) -> &mut Outline {
+    self.outlines.push(Outline {
+      text: text.to_string(),
+      xml_url: Some(url.to_string()),
+      ..Outline::default()
+    });
+
+    self.outlines.last_mut().expect("outline was just pushed")
+  }
+
+  /// Adds an [`Outline`] element with only a `text` attribute as a top-level
+  /// outline and returns it, so feeds can be added to it as a folder.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::Body;
+  ///
+  /// let mut body = Body::default();
+  /// body
+  ///   .push_folder("News")
+  ///   .add_feed("Feed Name", "https://example.com/");
+  ///
+  /// assert_eq!(body.outlines[0].text, "News");
+  /// assert_eq!(body.outlines[0].outlines[0].text, "Feed Name");
+  /// ```
+  pub fn push_folder(&mut self, text: &str) -> &mut Outline {
+    self.outlines.push(Outline {
+      text: text.to_string(),
+      ..Outline::default()
+    });
+
+    self.outlines.last_mut().expect("outline was just pushed")
+  }
+
+  /// Keeps only the top-level outlines for which `f` returns true, in their
+  /// original order. Removed outlines are removed together with all their
+  /// children.
+  ///
+  /// Nested outlines are never passed to `f`, use [`OPML::retain_feeds`] to
+  /// filter feeds at any depth instead.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::Body;
+  ///
+  /// let mut body = Body::default();
+  /// body.push_folder("News").add_feed("Feed", "https://example.com/");
+  /// body.push_feed("Loose Feed", "https://example.com/loose");
+  ///
+  /// body.retain_top_level(|outline| outline.xml_url.is_none());
+  /// assert_eq!(body.outlines.len(), 1);
+  /// assert_eq!(body.outlines[0].outlines[0].text, "Feed");
+  /// ```
+  pub fn retain_top_level<F>(&mut self, f: F)
+  where
+    F: FnMut(&Outline) -> bool,
+  {
+    self.outlines.retain(f);
+  }
+
+  /// Converts the body on its own to an XML string, without the surrounding
+  /// `<opml>` element and [`Head`]. The outlines are written the same way as
+  /// in [`OPML::to_string`].
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::Body;
+  ///
+  /// let mut body = Body::default();
+  /// body.push_feed("Feed", "https://example.com/");
+  ///
+  /// let expected = r#"<body><outline text="Feed" xmlUrl="https://example.com/"/></body>"#;
+  /// assert_eq!(body.to_string().unwrap(), expected);
+  /// ```
+  pub fn to_string(&self) -> Result<String, Error> {
+    let mut xml = String::new();
+    write::write_body_fmt(self, &mut xml, false)?;
+    Ok(xml)
+  }
+
+  /// Same as [`Body::to_string`], but with every outline on its own line and
+  /// indented by two spaces per level of nesting.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::Body;
+  ///
+  /// let mut body = Body::default();
+  /// body.push_folder("Folder").add_feed("Feed", "https://example.com/");
+  ///
+  /// let expected = r#"<body>
+  ///   <outline text="Folder">
+  ///     <outline text="Feed" xmlUrl="https://example.com/"/>
+  ///   </outline>
+  /// </body>"#;
+  /// assert_eq!(body.to_string_pretty().unwrap(), expected);
+  /// ```
+  pub fn to_string_pretty(&self) -> Result<String, Error> {
+    let mut xml = String::new();
+    write::write_body_fmt(self, &mut xml, true)?;
+    Ok(xml)
+  }
+}
+
 /// The [`Outline`] element.
-#[derive(
-  XmlWrite,
-  XmlRead,
-  PartialEq,
-  Eq,
-  Debug,
-  Clone,
-  Default,
-  Serialize,
-  Deserialize,
-)]
-#[xml(tag = "outline")]
+#[derive(Eq, Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "serde-camel-case", serde(rename_all = "camelCase"))]
 pub struct Outline {
   /// Every outline element must have at least a text attribute, which is what
   /// is displayed when an outliner opens the OPML document.
@@ -342,27 +2138,24 @@ pub struct Outline {
   /// replaced by an empty String if it is omitted.
   ///
   /// Text attributes may contain encoded HTML markup.
-  #[xml(default, attr = "text")]
   pub text: String,
 
   /// A string that indicates how the other attributes of the [`Outline`]
   /// should be interpreted.
-  #[xml(attr = "type")]
   pub r#type: Option<String>,
 
   /// Indicating whether the outline is commented or not. By convention if an
   /// outline is commented, all subordinate outlines are considered to also be
   /// commented.
-  #[xml(attr = "isComment")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "is_comment"))]
   pub is_comment: Option<bool>,
 
   /// Indicating whether a breakpoint is set on this outline. This attribute is
   /// mainly necessary for outlines used to edit scripts.
-  #[xml(attr = "isBreakpoint")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "is_breakpoint"))]
   pub is_breakpoint: Option<bool>,
 
   /// The date-time (RFC822) that this [`Outline`] element was created.
-  #[xml(attr = "created")]
   pub created: Option<String>,
 
   /// A string of comma-separated slash-delimited category strings, in the
@@ -370,41 +2163,81 @@ pub struct Outline {
   /// the category string should contain no slashes.
   ///
   /// [RSS 2.0 category]: https://cyber.law.harvard.edu/rss/rss.html#ltcategorygtSubelementOfLtitemgt
-  #[xml(attr = "category")]
   pub category: Option<String>,
 
   /// Child [`Outline`] elements of the current one.
-  #[xml(child = "outline")]
   pub outlines: Vec<Outline>,
 
   /// The HTTP address of the feed.
-  #[xml(attr = "xmlUrl")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "xml_url"))]
   pub xml_url: Option<String>,
 
   /// The top-level description element from the feed.
-  #[xml(attr = "description")]
   pub description: Option<String>,
 
   /// The top-level link element from the feed.
-  #[xml(attr = "htmlUrl")]
+  #[cfg_attr(feature = "serde-camel-case", serde(alias = "html_url"))]
   pub html_url: Option<String>,
 
   /// The top-level language element from the feed.
-  #[xml(attr = "language")]
   pub language: Option<String>,
 
   /// The top-level title element from the feed.
-  #[xml(attr = "title")]
   pub title: Option<String>,
 
   /// The version of the feed's format (such as RSS 0.91, 2.0, ...).
-  #[xml(attr = "version")]
   pub version: Option<String>,
 
   /// A link that can point to another OPML document or to something that can
   /// be displayed in a web browser.
-  #[xml(attr = "url")]
   pub url: Option<String>,
+
+  /// The index the outline had among its siblings in the parsed document,
+  /// only set when parsing with [`ParseOptions::record_original_index`]. This
+  /// isn't an attribute of the outline, so it's never written, serialized or
+  /// compared when checking outlines for equality, and can be used to restore
+  /// the original order after reordering.
+  #[serde(skip)]
+  pub original_index: Option<usize>,
+}
+
+impl PartialEq for Outline {
+  fn eq(&self, other: &Self) -> bool {
+    // Destructure so adding a field to `Outline` requires updating this.
+    let Outline {
+      text,
+      r#type,
+      is_comment,
+      is_breakpoint,
+      created,
+      category,
+      outlines,
+      xml_url,
+      description,
+      html_url,
+      language,
+      title,
+      version,
+      url,
+      // Where the outline was parsed from doesn't change its content.
+      original_index: _,
+    } = self;
+
+    *text == other.text
+      && *r#type == other.r#type
+      && *is_comment == other.is_comment
+      && *is_breakpoint == other.is_breakpoint
+      && *created == other.created
+      && *category == other.category
+      && *outlines == other.outlines
+      && *xml_url == other.xml_url
+      && *description == other.description
+      && *html_url == other.html_url
+      && *language == other.language
+      && *title == other.title
+      && *version == other.version
+      && *url == other.url
+  }
 }
 
 impl Outline {
@@ -438,4 +2271,381 @@ impl Outline {
 
     self
   }
+
+  /// Parses the `category` attribute into its comma-separated category
+  /// strings, with each one split into its slash-delimited segments. A leading
+  /// slash is ignored and returns an empty list when there is no category.
+  ///
+  /// Empty segments are kept, so malformed categories like `a,,b` can be
+  /// detected.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::Outline;
+  ///
+  /// let outline = Outline {
+  ///   category: Some("/Boston/Weather,rust".to_string()),
+  ///   ..Outline::default()
+  /// };
+  ///
+  /// assert_eq!(
+  ///   outline.categories(),
+  ///   vec![vec!["Boston", "Weather"], vec!["rust"]]
+  /// );
+  /// ```
+  pub fn categories(&self) -> Vec<Vec<&str>> {
+    match self.category.as_deref() {
+      Some(category) if !category.trim().is_empty() => {
+        category.split(',').map(category_segments).collect()
+      }
+      _ => vec![],
+    }
+  }
+
+  /// Returns the name to show for the outline: its `text`, or when that is
+  /// blank its `title`, `xml_url` or `html_url`, whichever isn't blank first.
+  /// The name is trimmed, and empty when all of them are blank or unset.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::Outline;
+  ///
+  /// let mut outline = Outline {
+  ///   xml_url: Some("https://example.com/feed.xml".to_string()),
+  ///   ..Outline::default()
+  /// };
+  /// assert_eq!(outline.display_name(), "https://example.com/feed.xml");
+  ///
+  /// outline.title = Some("Example".to_string());
+  /// assert_eq!(outline.display_name(), "Example");
+  /// ```
+  pub fn display_name(&self) -> &str {
+    [
+      Some(&self.text),
+      self.title.as_ref(),
+      self.xml_url.as_ref(),
+      self.html_url.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|name| name.trim())
+    .find(|name| !name.is_empty())
+    .unwrap_or_default()
+  }
+
+  /// Returns whether the outline is a folder, an outline with children. An
+  /// outline can be both a folder and a feed when it also has an `xml_url`.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::Outline;
+  ///
+  /// let mut folder = Outline::default();
+  /// assert!(!folder.is_folder());
+  ///
+  /// folder.add_feed("Feed Name", "https://example.com/");
+  /// assert!(folder.is_folder());
+  /// ```
+  pub fn is_folder(&self) -> bool {
+    !self.outlines.is_empty()
+  }
+
+  /// Returns the amount of outlines nested inside this one, at any depth. The
+  /// outline itself isn't counted.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::Outline;
+  ///
+  /// let mut folder = Outline::default();
+  /// folder.add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml");
+  /// folder.outlines.push(Outline::default());
+  /// folder.outlines[1].add_feed("Inside Rust", "https://blog.rust-lang.org/inside-rust/feed.xml");
+  ///
+  /// assert_eq!(folder.descendant_count(), 3);
+  /// ```
+  pub fn descendant_count(&self) -> usize {
+    Outlines::new(&self.outlines).count()
+  }
+
+  /// Returns the amount of outlines nested inside this one, at any depth, that
+  /// have an `xml_url`. The outline itself isn't counted.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::Outline;
+  ///
+  /// let mut folder = Outline::default();
+  /// folder.add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml");
+  /// folder.outlines.push(Outline::default());
+  /// folder.outlines[1].add_feed("Inside Rust", "https://blog.rust-lang.org/inside-rust/feed.xml");
+  ///
+  /// assert_eq!(folder.descendant_feed_count(), 2);
+  /// ```
+  pub fn descendant_feed_count(&self) -> usize {
+    Outlines::new(&self.outlines)
+      .filter(|outline| outline.xml_url.is_some())
+      .count()
+  }
+}
+
+/// Splits a single category string into its slash-delimited segments,
+/// ignoring a leading slash.
+fn category_segments(category: &str) -> Vec<&str> {
+  let category = category.trim();
+  category
+    .strip_prefix('/')
+    .unwrap_or(category)
+    .split('/')
+    .collect()
+}
+
+/// Recursively removes the feeds `keep` returns false for and the folders left
+/// empty by that, see [`OPML::retain_feeds`].
+fn retain_feeds<F>(outlines: &mut Vec<Outline>, keep: &F) -> usize
+where
+  F: Fn(&Outline) -> bool,
+{
+  let mut removed = 0;
+
+  outlines.retain_mut(|outline| {
+    if outline.xml_url.is_some() && !keep(outline) {
+      // Any feeds nested in the rejected feed are removed along with it.
+      removed += 1 + outline.descendant_feed_count();
+      return false;
+    }
+
+    if !outline.is_folder() {
+      return true;
+    }
+
+    removed += retain_feeds(&mut outline.outlines, keep);
+    outline.xml_url.is_some() || outline.is_folder()
+  });
+
+  removed
+}
+
+/// Recursively keeps the outlines that have a category containing the `query`
+/// segments, and the folders leading to them.
+fn filter_by_category(outlines: &[Outline], query: &[&str]) -> Vec<Outline> {
+  let mut filtered = vec![];
+
+  for outline in outlines {
+    let matches = outline.categories().iter().any(|segments| {
+      segments.windows(query.len()).any(|window| window == query)
+    });
+
+    if matches {
+      filtered.push(outline.clone());
+      continue;
+    }
+
+    let children = filter_by_category(&outline.outlines, query);
+    if !children.is_empty() {
+      let mut folder = outline.clone();
+      folder.outlines = children;
+      filtered.push(folder);
+    }
+  }
+
+  filtered
+}
+
+/// Compares two lists of outlines as multisets, see [`Body::eq_unordered`].
+fn outlines_eq_unordered(outlines: &[Outline], others: &[Outline]) -> bool {
+  if outlines.len() != others.len() {
+    return false;
+  }
+
+  let mut matched = vec![false; others.len()];
+
+  outlines.iter().all(|outline| {
+    let position = others.iter().enumerate().position(|(index, other)| {
+      !matched[index] && outline_eq_unordered(outline, other)
+    });
+
+    match position {
+      Some(index) => {
+        matched[index] = true;
+        true
+      }
+      None => false,
+    }
+  })
+}
+
+/// Compares two outlines, ignoring the order of their children.
+fn outline_eq_unordered(outline: &Outline, other: &Outline) -> bool {
+  // Destructure so adding a field to `Outline` requires updating this.
+  let Outline {
+    text,
+    r#type,
+    is_comment,
+    is_breakpoint,
+    created,
+    category,
+    outlines,
+    xml_url,
+    description,
+    html_url,
+    language,
+    title,
+    version,
+    url,
+    // The original position is ignored like the order of the outlines is.
+    original_index: _,
+  } = outline;
+
+  text == &other.text
+    && r#type == &other.r#type
+    && is_comment == &other.is_comment
+    && is_breakpoint == &other.is_breakpoint
+    && created == &other.created
+    && category == &other.category
+    && xml_url == &other.xml_url
+    && description == &other.description
+    && html_url == &other.html_url
+    && language == &other.language
+    && title == &other.title
+    && version == &other.version
+    && url == &other.url
+    && outlines_eq_unordered(outlines, &other.outlines)
+}
+
+/// Navigates down the visible outlines, counting down `remaining` for every
+/// line and returning the outline where it reaches 0.
+fn find_visible_line<'a>(
+  outlines: &'a [Outline],
+  expanded: &[&Outline],
+  remaining: &mut usize,
+) -> Option<&'a Outline> {
+  for outline in outlines {
+    *remaining -= 1;
+    if *remaining == 0 {
+      return Some(outline);
+    }
+
+    if is_expanded(expanded, outline) {
+      let found = find_visible_line(&outline.outlines, expanded, remaining);
+      if found.is_some() {
+        return found;
+      }
+    }
+  }
+
+  None
+}
+
+/// Checks whether `outline` is one of the `expanded` outlines, comparing by
+/// reference since identical outlines can appear in different places.
+fn is_expanded(expanded: &[&Outline], outline: &Outline) -> bool {
+  expanded
+    .iter()
+    .any(|expanded| std::ptr::eq(*expanded, outline))
+}
+
+/// Recursively sorts the outlines, see [`OPML::canonicalize`].
+fn canonicalize(outlines: &mut [Outline]) {
+  // Sort the children first, so the written XML used as the last key is
+  // canonical too.
+  for outline in outlines.iter_mut() {
+    canonicalize(&mut outline.outlines);
+  }
+
+  outlines.sort_by_cached_key(|outline| {
+    let xml = XmlWrite::to_string(outline)
+      .expect("writing an outline to a string can't fail");
+
+    // Folders go first because `false` sorts before `true`.
+    (
+      !outline.is_folder(),
+      outline.text.clone(),
+      outline.xml_url.clone(),
+      xml,
+    )
+  });
+}
+
+/// Normalizes a feed URL for comparisons, see [`OPML::find_feed`].
+fn normalize_feed_url(url: &str) -> String {
+  let url = url.trim();
+
+  let (scheme, rest) = match url.split_once("://") {
+    Some((scheme, rest)) => (format!("{}://", scheme.to_lowercase()), rest),
+    None => (String::new(), url),
+  };
+
+  let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+  let normalized = format!("{scheme}{}{path}", host.to_lowercase());
+  normalized.trim_end_matches('/').to_string()
+}
+
+/// Replaces `url` with `new` when it normalizes to `old`, returning whether
+/// it was replaced.
+fn replace_matching_url(
+  url: &mut Option<String>,
+  old: &str,
+  new: &str,
+) -> bool {
+  match url {
+    Some(current) if normalize_feed_url(current) == old => {
+      *current = new.to_string();
+      true
+    }
+    _ => false,
+  }
+}
+
+/// Recursively finds the first outline with an `xml_url` that normalizes to
+/// `url`.
+fn find_feed<'a>(outlines: &'a [Outline], url: &str) -> Option<&'a Outline> {
+  outlines.iter().find_map(|outline| match &outline.xml_url {
+    Some(xml_url) if normalize_feed_url(xml_url) == url => Some(outline),
+    _ => find_feed(&outline.outlines, url),
+  })
+}
+
+/// Recursively collects the normalized `xml_url`s of the outlines.
+fn collect_feed_urls(outlines: &[Outline], urls: &mut HashSet<String>) {
+  for outline in outlines {
+    if let Some(xml_url) = &outline.xml_url {
+      urls.insert(normalize_feed_url(xml_url));
+    }
+
+    collect_feed_urls(&outline.outlines, urls);
+  }
+}
+
+/// Recursively collects the outlines with an `xml_url` that isn't in `urls`.
+fn collect_missing_feeds<'a>(
+  outlines: &'a [Outline],
+  urls: &HashSet<String>,
+  missing: &mut Vec<&'a Outline>,
+) {
+  for outline in outlines {
+    if let Some(xml_url) = &outline.xml_url {
+      if !urls.contains(&normalize_feed_url(xml_url)) {
+        missing.push(outline);
+      }
+    }
+
+    collect_missing_feeds(&outline.outlines, urls, missing);
+  }
+}
+
+/// Recursively calls `f` on the outlines and their children, parents first.
+fn map_outlines_mut<F>(outlines: &mut [Outline], f: &mut F)
+where
+  F: FnMut(&mut Outline),
+{
+  for outline in outlines {
+    f(outline);
+    map_outlines_mut(&mut outline.outlines, f);
+  }
 }
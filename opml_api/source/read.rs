@@ -0,0 +1,353 @@
+//! Hand-written [`XmlRead`] implementations and readers for elements that
+//! need more lenient parsing than the derive macro provides, or that depend
+//! on the [`ParseOptions`].
+
+use hard_xml::{
+  xmlparser::{ElementEnd, Token, Tokenizer},
+  XmlError, XmlRead, XmlReader, XmlResult,
+};
+
+use crate::{Body, Error, Head, Outline, ParseOptions, OPML};
+
+/// The attribute names of the [`Outline`] element, used to find the correct
+/// name when matching case-insensitively.
+const OUTLINE_ATTRIBUTES: [&str; 13] = [
+  "text",
+  "type",
+  "isComment",
+  "isBreakpoint",
+  "created",
+  "category",
+  "xmlUrl",
+  "description",
+  "htmlUrl",
+  "language",
+  "title",
+  "version",
+  "url",
+];
+
+/// Splits text containing multiple concatenated documents into the text of
+/// each document, see [`OPML::from_str_multi`].
+pub(crate) fn split_documents(xml: &str) -> Vec<&str> {
+  let mut documents = vec![];
+  let mut rest = xml;
+
+  while let Some(start) = next_document_start(rest) {
+    documents.push(&rest[..start]);
+    rest = &rest[start..];
+  }
+
+  documents.push(rest);
+  documents
+}
+
+/// Tokenizes the first document in the text and returns the offset of the
+/// first thing that can't be part of it after its root element was closed,
+/// like the declaration or root element of the next document. Returns
+/// [`None`] when there is nothing else or the first document is invalid, so
+/// parsing reports the error.
+fn next_document_start(xml: &str) -> Option<usize> {
+  let mut tokenizer = Tokenizer::from(xml);
+  let mut depth = 0;
+  let mut root_closed = false;
+
+  loop {
+    let position = tokenizer.stream().pos();
+
+    match tokenizer.next()? {
+      Ok(Token::ElementStart { .. }) => depth += 1,
+      Ok(Token::ElementEnd {
+        end: ElementEnd::Close(..) | ElementEnd::Empty,
+        ..
+      }) => {
+        depth -= 1;
+        root_closed = depth == 0;
+      }
+      Ok(_) => (),
+      // After the root element only comments, processing instructions and
+      // whitespace are allowed, anything else starts the next document.
+      Err(_) if root_closed => {
+        let rest = &xml[position..];
+        return Some(position + rest.len() - rest.trim_start().len());
+      }
+      Err(_) => return None,
+    }
+  }
+}
+
+/// Reads an [`OPML`] element using the given options.
+pub(crate) fn read_opml(
+  reader: &mut XmlReader,
+  options: &ParseOptions,
+) -> Result<OPML, Error> {
+  let mut version = None;
+  let mut head = None;
+  let mut body = None;
+  let mut outline_count = 0;
+
+  // The declaration can only be the very first token of the document.
+  let source_encoding = match reader.peek() {
+    Some(Ok(Token::Declaration { encoding, .. })) => {
+      encoding.map(|encoding| encoding.as_str().to_string())
+    }
+    _ => None,
+  };
+
+  reader.read_till_element_start("opml")?;
+
+  while let Some((key, value)) = reader.find_attribute()? {
+    if key == "version" {
+      version = Some(value.into_owned());
+    }
+  }
+
+  if !is_empty_element(reader)? {
+    while let Some(tag) = reader.find_element_start(Some("opml"))? {
+      match tag {
+        "head" => head = Some(Head::from_reader(reader)?),
+        "body" => body = Some(read_body(reader, options, &mut outline_count)?),
+        _ => skip_element(reader, tag)?,
+      }
+    }
+  }
+
+  Ok(OPML {
+    version: version.ok_or_else(|| missing_field("OPML", "version"))?,
+    head,
+    body: body.ok_or_else(|| missing_field("OPML", "body"))?,
+    source_encoding,
+  })
+}
+
+/// Reads a [`Body`] element using the given options, adding the amount of
+/// outlines read to `outline_count`.
+fn read_body(
+  reader: &mut XmlReader,
+  options: &ParseOptions,
+  outline_count: &mut usize,
+) -> Result<Body, Error> {
+  let mut body = Body::default();
+
+  reader.read_till_element_start("body")?;
+  while reader.find_attribute()?.is_some() {}
+
+  if is_empty_element(reader)? {
+    return Ok(body);
+  }
+
+  while let Some(tag) = reader.find_element_start(Some("body"))? {
+    match tag {
+      "outline" => {
+        let outline = read_outline(reader, options, outline_count)?;
+        push_outline(&mut body.outlines, outline, options);
+      }
+      _ => skip_element(reader, tag)?,
+    }
+  }
+
+  Ok(body)
+}
+
+/// Reads an [`Outline`] element and its children using the given options,
+/// adding the amount of outlines read to `outline_count`.
+///
+/// Returns [`Error::TooManyOutlines`] as soon as the count goes over the
+/// `max_outlines` option.
+fn read_outline(
+  reader: &mut XmlReader,
+  options: &ParseOptions,
+  outline_count: &mut usize,
+) -> Result<Outline, Error> {
+  *outline_count += 1;
+  if let Some(max_outlines) = options.max_outlines {
+    if *outline_count > max_outlines {
+      return Err(Error::TooManyOutlines(max_outlines));
+    }
+  }
+
+  let mut outline = Outline::default();
+
+  reader.read_till_element_start("outline")?;
+
+  while let Some((key, value)) = reader.find_attribute()? {
+    let key = if options.case_insensitive_attributes {
+      OUTLINE_ATTRIBUTES
+        .into_iter()
+        .find(|name| name.eq_ignore_ascii_case(key))
+        .unwrap_or(key)
+    } else {
+      key
+    };
+
+    let value = value.into_owned();
+    match key {
+      "text" => outline.text = value,
+      "type" => outline.r#type = Some(value),
+      "isComment" => outline.is_comment = Some(parse_bool(&value)?),
+      "isBreakpoint" => outline.is_breakpoint = Some(parse_bool(&value)?),
+      "created" => outline.created = Some(value),
+      "category" => outline.category = Some(value),
+      "xmlUrl" => outline.xml_url = Some(value),
+      "description" => outline.description = Some(value),
+      "htmlUrl" => outline.html_url = Some(value),
+      "language" => outline.language = Some(value),
+      "title" => outline.title = Some(value),
+      "version" => outline.version = Some(value),
+      "url" => outline.url = Some(value),
+      _ => (),
+    }
+  }
+
+  if is_empty_element(reader)? {
+    return Ok(outline);
+  }
+
+  while let Some(tag) = reader.find_element_start(Some("outline"))? {
+    match tag {
+      "outline" => {
+        let child = read_outline(reader, options, outline_count)?;
+        push_outline(&mut outline.outlines, child, options);
+      }
+      _ => skip_element(reader, tag)?,
+    }
+  }
+
+  Ok(outline)
+}
+
+/// Adds a read outline to its siblings, recording its index when the options
+/// say so.
+fn push_outline(
+  outlines: &mut Vec<Outline>,
+  mut outline: Outline,
+  options: &ParseOptions,
+) {
+  if options.record_original_index {
+    outline.original_index = Some(outlines.len());
+  }
+
+  outlines.push(outline);
+}
+
+impl<'a> XmlRead<'a> for OPML {
+  fn from_reader(reader: &mut XmlReader<'a>) -> XmlResult<Self> {
+    read_opml(reader, &ParseOptions::default()).map_err(into_xml_error)
+  }
+}
+
+impl<'a> XmlRead<'a> for Outline {
+  fn from_reader(reader: &mut XmlReader<'a>) -> XmlResult<Self> {
+    read_outline(reader, &ParseOptions::default(), &mut 0)
+      .map_err(into_xml_error)
+  }
+}
+
+impl<'a> XmlRead<'a> for Head {
+  fn from_reader(reader: &mut XmlReader<'a>) -> XmlResult<Self> {
+    let mut head = Head::default();
+
+    reader.read_till_element_start("head")?;
+
+    // The head element has no attributes, so skip any that are present.
+    while reader.find_attribute()?.is_some() {}
+
+    if is_empty_element(reader)? {
+      return Ok(head);
+    }
+
+    while let Some(tag) = reader.find_element_start(Some("head"))? {
+      // Skip the element start, the text readers start from its attributes.
+      reader.next().transpose()?;
+
+      match tag {
+        "title" => head.title = Some(read_string(reader, tag)?),
+        "dateCreated" => head.date_created = Some(read_string(reader, tag)?),
+        "dateModified" => head.date_modified = Some(read_string(reader, tag)?),
+        "ownerName" => head.owner_name = Some(read_string(reader, tag)?),
+        "ownerEmail" => head.owner_email = Some(read_string(reader, tag)?),
+        "ownerId" => head.owner_id = Some(read_string(reader, tag)?),
+        "docs" => head.docs = Some(read_string(reader, tag)?),
+        "expansionState" => {
+          head.expansion_state = Some(read_string(reader, tag)?)
+        }
+        "vertScrollState" => head.vert_scroll_state = read_number(reader, tag)?,
+        "windowTop" => head.window_top = read_number(reader, tag)?,
+        "windowLeft" => head.window_left = read_number(reader, tag)?,
+        "windowBottom" => head.window_bottom = read_number(reader, tag)?,
+        "windowRight" => head.window_right = read_number(reader, tag)?,
+        _ => reader.read_to_end(tag)?,
+      }
+    }
+
+    Ok(head)
+  }
+}
+
+/// Consumes the end of an element's start tag, returning whether the element
+/// is empty (self-closing) and has no children.
+fn is_empty_element(reader: &mut XmlReader) -> XmlResult<bool> {
+  Ok(matches!(
+    reader.next().transpose()?,
+    Some(Token::ElementEnd {
+      end: ElementEnd::Empty,
+      ..
+    })
+  ))
+}
+
+/// Skips the unknown element `tag` and all of its children.
+fn skip_element(reader: &mut XmlReader, tag: &str) -> XmlResult<()> {
+  reader.next().transpose()?;
+  reader.read_to_end(tag)
+}
+
+/// Converts an [`Error`] from the readers back into the [`XmlError`] it wraps,
+/// for the [`XmlRead`] implementations.
+fn into_xml_error(error: Error) -> XmlError {
+  match error {
+    Error::XmlError(error) => error,
+    // Without the `max_outlines` option only XML errors can occur.
+    error => XmlError::FromStr(Box::new(error)),
+  }
+}
+
+/// Creates the same error the derive macro returns for missing fields.
+fn missing_field(name: &str, field: &str) -> XmlError {
+  XmlError::MissingField {
+    name: name.to_string(),
+    field: field.to_string(),
+  }
+}
+
+/// Parses a boolean attribute, accepting the same values as the derive macro.
+fn parse_bool(value: &str) -> XmlResult<bool> {
+  match value {
+    "t" | "true" | "y" | "yes" | "on" | "1" => Ok(true),
+    "f" | "false" | "n" | "no" | "off" | "0" => Ok(false),
+    _ => value
+      .parse()
+      .map_err(|error| XmlError::FromStr(Box::new(error))),
+  }
+}
+
+/// Reads the text content of the element `tag`.
+fn read_string(reader: &mut XmlReader, tag: &str) -> XmlResult<String> {
+  Ok(reader.read_text(tag)?.into_owned())
+}
+
+/// Reads the text content of the element `tag` as a number. Empty elements
+/// (like `<windowTop/>`) are treated as absent and result in [`None`].
+fn read_number(reader: &mut XmlReader, tag: &str) -> XmlResult<Option<i32>> {
+  let text = reader.read_text(tag)?;
+  let text = text.trim();
+
+  if text.is_empty() {
+    return Ok(None);
+  }
+
+  text
+    .parse()
+    .map(Some)
+    .map_err(|error| XmlError::FromStr(Box::new(error)))
+}
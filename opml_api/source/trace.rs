@@ -0,0 +1,44 @@
+//! Tracing instrumentation, enabled with the `tracing` feature.
+
+use std::time::Duration;
+
+use crate::{Error, OPML};
+
+/// Emits an event with the outcome of parsing a document. Only fields with
+/// few distinct values are recorded, so no text or URLs from the document.
+pub(crate) fn parsed(result: &Result<OPML, Error>, duration: Duration) {
+  let duration_us = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+
+  match result {
+    Ok(opml) => tracing::debug!(
+      version = %opml.version,
+      feeds = opml.feeds().count(),
+      duration_us,
+      "parsed OPML document"
+    ),
+    Err(error) => tracing::debug!(
+      error = error_kind(error),
+      duration_us,
+      "failed to parse OPML document"
+    ),
+  }
+}
+
+/// Returns the name of the error's variant, since the messages of some errors
+/// include text from the document.
+fn error_kind(error: &Error) -> &'static str {
+  match error {
+    Error::BodyHasNoOutlines => "BodyHasNoOutlines",
+    Error::Encoding { .. } => "Encoding",
+    Error::FolderNotFound(_) => "FolderNotFound",
+    Error::InvalidBookmarks(_) => "InvalidBookmarks",
+    Error::InvalidDocument { .. } => "InvalidDocument",
+    Error::InvalidMove(_) => "InvalidMove",
+    Error::InvalidPath(_) => "InvalidPath",
+    Error::IoError(_) => "IoError",
+    Error::NotAFolder(_) => "NotAFolder",
+    Error::TooManyOutlines(_) => "TooManyOutlines",
+    Error::UnsupportedVersion(_) => "UnsupportedVersion",
+    Error::XmlError(_) => "XmlError",
+  }
+}
@@ -0,0 +1,148 @@
+//! Validation of documents beyond what's required to parse them.
+
+use thiserror::Error;
+
+use crate::OPML;
+
+/// Problems found by [`OPML::validate`].
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+  /// The [`Head`](crate::Head)'s `docs` is not empty but also not a URL.
+  #[error("Invalid docs URL: {0:?}")]
+  InvalidDocsUrl(String),
+
+  /// An [`Outline`](crate::Outline)'s `category` has an empty category or
+  /// an empty segment in one, like `a,,b`, `a,` or `/a//b`.
+  #[error("Malformed category {value:?} in outline {outline:?}")]
+  MalformedCategory {
+    /// The `text` of the outline.
+    outline: String,
+    /// The `category` of the outline.
+    value: String,
+  },
+
+  /// A date-time field is set but isn't in the RFC822 format the spec
+  /// requires. Only checked with the `date` feature.
+  #[error("Invalid date in {field}: {value:?}")]
+  InvalidDate {
+    /// The name of the field as it appears in the XML, like `dateCreated`.
+    field: &'static str,
+    /// The value that couldn't be parsed.
+    value: String,
+  },
+}
+
+impl OPML {
+  /// Checks the document for values that parse fine but don't follow the
+  /// spec, returning all the problems that were found.
+  ///
+  /// Currently this checks that:
+  /// * the [`Head`](crate::Head)'s `docs` is a URL when it isn't empty,
+  /// * every outline's `category` has no empty categories or segments,
+  /// * with the `date` feature, the [`Head`](crate::Head)'s `date_created`
+  ///   and `date_modified` and every outline's `created` are RFC822
+  ///   date-times when they are set.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// use opml::{Head, OPML, ValidationError};
+  ///
+  /// let mut opml = OPML::default();
+  /// assert!(opml.validate().is_empty());
+  ///
+  /// opml.head = Some(Head {
+  ///   docs: Some("not a url".to_string()),
+  ///   ..Head::default()
+  /// });
+  /// assert_eq!(
+  ///   opml.validate(),
+  ///   vec![ValidationError::InvalidDocsUrl("not a url".to_string())]
+  /// );
+  /// ```
+  pub fn validate(&self) -> Vec<ValidationError> {
+    let mut errors = vec![];
+
+    if let Some(head) = &self.head {
+      if let Some(docs) = head.docs_url() {
+        if !is_url(docs) {
+          errors.push(ValidationError::InvalidDocsUrl(docs.to_string()));
+        }
+      }
+    }
+
+    for outline in self.outlines() {
+      let Some(category) = &outline.category else {
+        continue;
+      };
+
+      let malformed = outline
+        .categories()
+        .iter()
+        .any(|segments| segments.iter().any(|segment| segment.is_empty()));
+
+      if malformed {
+        errors.push(ValidationError::MalformedCategory {
+          outline: outline.text.clone(),
+          value: category.clone(),
+        });
+      }
+    }
+
+    #[cfg(feature = "date")]
+    {
+      let head_dates = self.head.iter().flat_map(|head| {
+        [
+          ("dateCreated", &head.date_created),
+          ("dateModified", &head.date_modified),
+        ]
+      });
+      let outline_dates =
+        self.outlines().map(|outline| ("created", &outline.created));
+
+      for (field, value) in head_dates.chain(outline_dates) {
+        let Some(value) = value else {
+          continue;
+        };
+
+        if crate::date::parse_rfc822(value).is_none() {
+          errors.push(ValidationError::InvalidDate {
+            field,
+            value: value.clone(),
+          });
+        }
+      }
+    }
+
+    errors
+  }
+}
+
+/// Checks whether `url` looks like an absolute URL: a scheme followed by `://`
+/// and a host, without any whitespace.
+pub(crate) fn is_url(url: &str) -> bool {
+  let Some((scheme, rest)) = url.split_once("://") else {
+    return false;
+  };
+
+  let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+    && scheme
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+  let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+
+  valid_scheme && !host.is_empty() && !url.contains(char::is_whitespace)
+}
+
+/// Checks whether `email` looks like an email address: a local part and a
+/// domain separated by a single `@`, without any whitespace.
+pub(crate) fn is_email(email: &str) -> bool {
+  let Some((local, domain)) = email.split_once('@') else {
+    return false;
+  };
+
+  !local.is_empty()
+    && !domain.is_empty()
+    && !domain.contains('@')
+    && !email.contains(char::is_whitespace)
+}
@@ -0,0 +1,273 @@
+//! Hand-written writers for elements whose output depends on the
+//! [`WriteOptions`].
+
+use std::{
+  fmt,
+  io::{self, Write},
+};
+
+use hard_xml::{utils::xml_escape, XmlResult, XmlWrite, XmlWriter};
+
+use crate::{
+  validation::{is_email, is_url},
+  Body, Error, Head, HeadField, Outline, WriteOptions, OPML,
+};
+
+/// Writes an [`OPML`] element into a [`fmt::Write`] sink using the given
+/// options, see [`write_opml`].
+pub(crate) fn write_opml_fmt<W: fmt::Write>(
+  opml: &OPML,
+  writer: &mut W,
+  options: &WriteOptions,
+) -> Result<(), Error> {
+  let mut writer = XmlWriter::new(FmtWriter(writer));
+  Ok(write_opml(opml, &mut writer, options)?)
+}
+
+/// Writes a [`Body`] element on its own into a [`fmt::Write`] sink, indenting
+/// the outlines on their own lines when `pretty` is set.
+pub(crate) fn write_body_fmt<W: fmt::Write>(
+  body: &Body,
+  writer: &mut W,
+  pretty: bool,
+) -> Result<(), Error> {
+  let mut writer = XmlWriter::new(FmtWriter(writer));
+  let depth = pretty.then_some(0);
+  Ok(write_body(
+    body,
+    &mut writer,
+    &WriteOptions::default(),
+    depth,
+  )?)
+}
+
+/// Adapts a [`fmt::Write`] sink to [`io::Write`], so [`XmlWriter`] can write
+/// into it directly.
+struct FmtWriter<'a, W: fmt::Write>(&'a mut W);
+
+impl<W: fmt::Write> Write for FmtWriter<'_, W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    // The writers only ever write whole strings, so the bytes are always
+    // valid UTF-8.
+    let text = std::str::from_utf8(buf)
+      .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    self.0.write_str(text).map_err(io::Error::other)?;
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+/// Writes an [`OPML`] element using the given options, producing the same
+/// output as the derive macro when no options are set.
+pub(crate) fn write_opml<W: Write>(
+  opml: &OPML,
+  writer: &mut XmlWriter<W>,
+  options: &WriteOptions,
+) -> XmlResult<()> {
+  if options.byte_order_mark {
+    write!(writer.inner, "\u{FEFF}")?;
+  }
+
+  // The output is always UTF-8, so the encoding the document was parsed from
+  // is only declared when the caller asks for it and transcodes the output.
+  if options.xml_declaration {
+    let encoding = options.declared_encoding.as_deref().unwrap_or("UTF-8");
+    write!(
+      writer.inner,
+      r#"<?xml version="1.0" encoding="{}"?>"#,
+      xml_escape(encoding)
+    )?;
+  }
+
+  writer.write_element_start("opml")?;
+  write_attribute(writer, "version", &opml.version, options)?;
+  writer.write_element_end_open()?;
+
+  if let Some(head) = &opml.head {
+    let mut omitted = options.omit_head_fields.clone();
+    if options.omit_invalid_head_fields {
+      omitted.extend(invalid_head_fields(head));
+    }
+
+    if omitted.is_empty() {
+      head.to_writer(writer)?;
+    } else {
+      redact_head(head, &omitted).to_writer(writer)?;
+    }
+  }
+
+  write_body(&opml.body, writer, options, None)?;
+  writer.write_element_end_close("opml")?;
+  Ok(())
+}
+
+impl XmlWrite for OPML {
+  fn to_writer<W: Write>(&self, writer: &mut XmlWriter<W>) -> XmlResult<()> {
+    write_opml(self, writer, &WriteOptions::default())
+  }
+}
+
+impl XmlWrite for Outline {
+  fn to_writer<W: Write>(&self, writer: &mut XmlWriter<W>) -> XmlResult<()> {
+    write_outline(self, writer, &WriteOptions::default(), None)
+  }
+}
+
+/// Writes a [`Body`] element and its outlines using the given options. When
+/// a `depth` is given every outline is written on its own line, indented
+/// relative to it, see [`write_indent`].
+fn write_body<W: Write>(
+  body: &Body,
+  writer: &mut XmlWriter<W>,
+  options: &WriteOptions,
+  depth: Option<usize>,
+) -> XmlResult<()> {
+  writer.write_element_start("body")?;
+
+  if body.outlines.is_empty() {
+    writer.write_element_end_empty()?;
+    return Ok(());
+  }
+
+  writer.write_element_end_open()?;
+  let child_depth = depth.map(|depth| depth + 1);
+  for outline in &body.outlines {
+    write_indent(writer, child_depth)?;
+    write_outline(outline, writer, options, child_depth)?;
+  }
+  write_indent(writer, depth)?;
+  writer.write_element_end_close("body")?;
+  Ok(())
+}
+
+/// Writes an [`Outline`] element and its children using the given options,
+/// with the attributes in the order they are defined in. The
+/// `original_index` isn't an attribute and is never written. The `depth` is
+/// the same as for [`write_body`].
+fn write_outline<W: Write>(
+  outline: &Outline,
+  writer: &mut XmlWriter<W>,
+  options: &WriteOptions,
+  depth: Option<usize>,
+) -> XmlResult<()> {
+  let bool_str = |value: bool| if value { "true" } else { "false" };
+
+  let attributes = [
+    ("text", Some(outline.text.as_str())),
+    ("type", outline.r#type.as_deref()),
+    ("isComment", outline.is_comment.map(bool_str)),
+    ("isBreakpoint", outline.is_breakpoint.map(bool_str)),
+    ("created", outline.created.as_deref()),
+    ("category", outline.category.as_deref()),
+    ("xmlUrl", outline.xml_url.as_deref()),
+    ("description", outline.description.as_deref()),
+    ("htmlUrl", outline.html_url.as_deref()),
+    ("language", outline.language.as_deref()),
+    ("title", outline.title.as_deref()),
+    ("version", outline.version.as_deref()),
+    ("url", outline.url.as_deref()),
+  ];
+
+  writer.write_element_start("outline")?;
+  for (key, value) in attributes {
+    if let Some(value) = value {
+      write_attribute(writer, key, value, options)?;
+    }
+  }
+
+  if outline.outlines.is_empty() {
+    writer.write_element_end_empty()?;
+    return Ok(());
+  }
+
+  writer.write_element_end_open()?;
+  let child_depth = depth.map(|depth| depth + 1);
+  for child in &outline.outlines {
+    write_indent(writer, child_depth)?;
+    write_outline(child, writer, options, child_depth)?;
+  }
+  write_indent(writer, depth)?;
+  writer.write_element_end_close("outline")?;
+  Ok(())
+}
+
+/// Starts a new line indented by two spaces per level of `depth`, or writes
+/// nothing when there is no depth.
+fn write_indent<W: Write>(
+  writer: &mut XmlWriter<W>,
+  depth: Option<usize>,
+) -> io::Result<()> {
+  match depth {
+    Some(depth) => write!(writer.inner, "\n{}", "  ".repeat(depth)),
+    None => Ok(()),
+  }
+}
+
+/// Writes an attribute quoted as the options say. Both quote characters are
+/// always escaped, so the value is correct in either style.
+fn write_attribute<W: Write>(
+  writer: &mut XmlWriter<W>,
+  key: &str,
+  value: &str,
+  options: &WriteOptions,
+) -> io::Result<()> {
+  if options.single_quote_attributes {
+    write!(writer.inner, " {}='{}'", key, xml_escape(value))
+  } else {
+    writer.write_attribute(key, value)
+  }
+}
+
+/// Returns the [`Head`] fields that are set but don't have a valid value: an
+/// `owner_email` that isn't an email address or an `owner_id` that isn't a
+/// URL.
+fn invalid_head_fields(head: &Head) -> Vec<HeadField> {
+  let is_invalid = |value: &Option<String>, is_valid: fn(&str) -> bool| {
+    value
+      .as_deref()
+      .is_some_and(|value| !is_valid(value.trim()))
+  };
+
+  let mut invalid = vec![];
+  if is_invalid(&head.owner_email, is_email) {
+    invalid.push(HeadField::OwnerEmail);
+  }
+  if is_invalid(&head.owner_id, is_url) {
+    invalid.push(HeadField::OwnerId);
+  }
+
+  #[cfg(feature = "tracing")]
+  for field in &invalid {
+    tracing::debug!(?field, "omitting invalid head field");
+  }
+
+  invalid
+}
+
+/// Returns a copy of the [`Head`] with the given fields removed.
+fn redact_head(head: &Head, fields: &[HeadField]) -> Head {
+  let mut head = head.clone();
+
+  for field in fields {
+    match field {
+      HeadField::Title => head.title = None,
+      HeadField::DateCreated => head.date_created = None,
+      HeadField::DateModified => head.date_modified = None,
+      HeadField::OwnerName => head.owner_name = None,
+      HeadField::OwnerEmail => head.owner_email = None,
+      HeadField::OwnerId => head.owner_id = None,
+      HeadField::Docs => head.docs = None,
+      HeadField::ExpansionState => head.expansion_state = None,
+      HeadField::VertScrollState => head.vert_scroll_state = None,
+      HeadField::WindowTop => head.window_top = None,
+      HeadField::WindowLeft => head.window_left = None,
+      HeadField::WindowBottom => head.window_bottom = None,
+      HeadField::WindowRight => head.window_right = None,
+    }
+  }
+
+  head
+}
@@ -0,0 +1,154 @@
+use std::fs::read_to_string as read;
+
+use opml::*;
+
+#[test]
+fn test_to_netscape_bookmarks() {
+  let mut opml = OPML {
+    head: Some(Head {
+      title: Some("Feeds & Links".to_string()),
+      ..Head::default()
+    }),
+    ..OPML::default()
+  };
+
+  let mut folder = Outline {
+    text: "Rust".to_string(),
+    ..Outline::default()
+  };
+  folder.add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml");
+  folder.outlines.push(Outline {
+    text: "Inside \"Rust\"".to_string(),
+    xml_url: Some(
+      "https://blog.rust-lang.org/inside-rust/feed.xml".to_string(),
+    ),
+    html_url: Some("https://blog.rust-lang.org/inside-rust/".to_string()),
+    ..Outline::default()
+  });
+
+  opml.body.outlines.push(folder);
+  opml.body.outlines.push(Outline {
+    text: "No URL".to_string(),
+    ..Outline::default()
+  });
+
+  assert_eq!(
+    opml.to_netscape_bookmarks(),
+    read("tests/samples/bookmarks.html").unwrap()
+  );
+}
+
+#[cfg(feature = "bookmarks")]
+#[test]
+fn test_netscape_bookmarks_round_trip() {
+  let opml = OPML::from_netscape_bookmarks(
+    &read("tests/samples/bookmarks.html").unwrap(),
+  )
+  .unwrap();
+
+  assert_eq!(
+    opml.head.as_ref().and_then(|head| head.title.as_deref()),
+    Some("Feeds & Links")
+  );
+
+  assert_eq!(
+    opml.body.outlines,
+    vec![Outline {
+      text: "Rust".to_string(),
+      outlines: vec![
+        Outline {
+          text: "Rust Blog".to_string(),
+          xml_url: Some("https://blog.rust-lang.org/feed.xml".to_string()),
+          ..Outline::default()
+        },
+        Outline {
+          text: "Inside \"Rust\"".to_string(),
+          xml_url: Some(
+            "https://blog.rust-lang.org/inside-rust/feed.xml".to_string(),
+          ),
+          html_url: Some("https://blog.rust-lang.org/inside-rust/".to_string(),),
+          ..Outline::default()
+        },
+      ],
+      ..Outline::default()
+    }]
+  );
+
+  let exported = opml.to_netscape_bookmarks();
+  assert_eq!(exported, read("tests/samples/bookmarks.html").unwrap());
+  assert_eq!(OPML::from_netscape_bookmarks(&exported).unwrap(), opml);
+}
+
+#[cfg(feature = "bookmarks")]
+#[test]
+fn test_from_netscape_bookmarks_quoted_angle_bracket() {
+  let html = r#"<DL><p>
+    <DT><A HREF="https://example.com/?a>b" FEEDURL='https://example.com/feed?x>y'>Example</A>
+  </DL><p>"#;
+
+  let opml = OPML::from_netscape_bookmarks(html).unwrap();
+  assert_eq!(
+    opml.body.outlines,
+    vec![Outline {
+      text: "Example".to_string(),
+      xml_url: Some("https://example.com/feed?x>y".to_string()),
+      html_url: Some("https://example.com/?a>b".to_string()),
+      ..Outline::default()
+    }]
+  );
+}
+
+#[cfg(feature = "bookmarks")]
+#[test]
+fn test_from_netscape_bookmarks_feed_url() {
+  let html = r#"<dl><p>
+    <dt><h3>Empty</h3>
+    <dt><a href='https://example.com/' feedurl=https://example.com/feed>Example</a>
+  </dl><p>"#;
+
+  let opml = OPML::from_netscape_bookmarks(html).unwrap();
+  assert_eq!(
+    opml.body.outlines,
+    vec![
+      Outline {
+        text: "Empty".to_string(),
+        ..Outline::default()
+      },
+      Outline {
+        text: "Example".to_string(),
+        xml_url: Some("https://example.com/feed".to_string()),
+        html_url: Some("https://example.com/".to_string()),
+        ..Outline::default()
+      },
+    ]
+  );
+}
+
+#[cfg(feature = "bookmarks")]
+#[test]
+fn test_from_netscape_bookmarks_malformed() {
+  for (html, message) in [
+    ("<DL><p><DT><A HREF=\"x\"", "unterminated tag at byte 11"),
+    ("<DL><p></DL></DL>", "found a </DL> without a matching <DL>"),
+    ("<DL><p><DL><p></DL>", "1 unclosed <DL> list(s)"),
+    ("<TITLE>Nothing</TITLE>", "no bookmarks list (<DL>) found"),
+    ("<DL><p></DL><p>", "no bookmarks found"),
+    (
+      "<DL><p><DT><A HREF=\"a\">One\n<DT><A HREF=\"b\">Two</A></DL>",
+      "found <A> before <A> was closed",
+    ),
+    (
+      "<DL><p><DT><H3>Folder</DL>",
+      "found </DL> before <H3> was closed",
+    ),
+    (
+      "<DL><p></DL><TITLE>Bookmarks",
+      "found the end of the file before <TITLE> was closed",
+    ),
+  ] {
+    assert_eq!(
+      OPML::from_netscape_bookmarks(html).unwrap_err().to_string(),
+      format!("Invalid bookmarks file: {message}")
+    );
+  }
+}
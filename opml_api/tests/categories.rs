@@ -0,0 +1,91 @@
+use opml::*;
+
+fn categorized_document() -> OPML {
+  let mut opml = OPML::default();
+
+  let mut folder = Outline {
+    text: "Folder".to_string(),
+    ..Outline::default()
+  };
+  folder.outlines.push(Outline {
+    text: "Tech".to_string(),
+    category: Some("/News/Tech".to_string()),
+    ..Outline::default()
+  });
+  folder.outlines.push(Outline {
+    text: "Sports".to_string(),
+    category: Some("/Sports".to_string()),
+    ..Outline::default()
+  });
+
+  opml.body.outlines.push(folder);
+  opml.body.outlines.push(Outline {
+    text: "World".to_string(),
+    category: Some("/Blogs, /News/World".to_string()),
+    ..Outline::default()
+  });
+  opml
+}
+
+#[test]
+fn test_filter_by_category_keeps_ancestors() {
+  let filtered = categorized_document().filter_by_category("/News");
+
+  let texts = filtered
+    .body
+    .outlines
+    .iter()
+    .map(|outline| outline.text.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(texts, vec!["Folder", "World"]);
+
+  let folder = &filtered.body.outlines[0];
+  assert_eq!(folder.outlines.len(), 1);
+  assert_eq!(folder.outlines[0].text, "Tech");
+}
+
+#[test]
+fn test_filter_by_category_nested_segment() {
+  let filtered = categorized_document().filter_by_category("Tech");
+  assert_eq!(filtered.body.outlines[0].outlines[0].text, "Tech");
+  assert_eq!(filtered.body.outlines.len(), 1);
+}
+
+#[test]
+fn test_filter_by_category_no_matches() {
+  let document = categorized_document();
+  let filtered = document.filter_by_category("/Cooking");
+
+  assert!(filtered.body.outlines.is_empty());
+  assert_eq!(filtered.head, document.head);
+  assert!(document.filter_by_category("/").body.outlines.is_empty());
+}
+
+#[test]
+fn test_try_filter_by_category() {
+  let document = categorized_document();
+
+  let filtered = document.try_filter_by_category("/News").unwrap();
+  assert_eq!(filtered, document.filter_by_category("/News"));
+  assert!(OPML::from_str(&filtered.to_string().unwrap()).is_ok());
+
+  for category in ["/Cooking", "/"] {
+    assert!(matches!(
+      document.try_filter_by_category(category),
+      Err(Error::BodyHasNoOutlines)
+    ));
+  }
+}
+
+#[test]
+fn test_categories_keeps_empty_segments() {
+  let outline = Outline {
+    category: Some("a,,b/".to_string()),
+    ..Outline::default()
+  };
+
+  assert_eq!(
+    outline.categories(),
+    vec![vec!["a"], vec![""], vec!["b", ""]]
+  );
+}
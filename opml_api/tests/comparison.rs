@@ -0,0 +1,125 @@
+use opml::*;
+
+fn folder(text: &str, feeds: &[(&str, &str)]) -> Outline {
+  let mut folder = Outline {
+    text: text.to_string(),
+    ..Outline::default()
+  };
+
+  for (name, url) in feeds {
+    folder.add_feed(name, url);
+  }
+
+  folder
+}
+
+#[test]
+fn test_eq_unordered_nested() {
+  let first = Body {
+    outlines: vec![
+      folder("A", &[("1", "https://1"), ("2", "https://2")]),
+      folder("B", &[("3", "https://3")]),
+    ],
+  };
+  let second = Body {
+    outlines: vec![
+      folder("B", &[("3", "https://3")]),
+      folder("A", &[("2", "https://2"), ("1", "https://1")]),
+    ],
+  };
+
+  assert!(first.eq_unordered(&second));
+  assert!(second.eq_unordered(&first));
+}
+
+#[test]
+fn test_eq_unordered_requires_same_nesting() {
+  let nested = Body {
+    outlines: vec![folder("A", &[("1", "https://1")])],
+  };
+  let flat = Body {
+    outlines: vec![folder("A", &[]), folder("1", &[])],
+  };
+
+  assert!(!nested.eq_unordered(&flat));
+}
+
+#[test]
+fn test_eq_unordered_counts_duplicates() {
+  let outline = folder("A", &[]);
+  let twice = Body {
+    outlines: vec![outline.clone(), outline.clone(), folder("B", &[])],
+  };
+  let once = Body {
+    outlines: vec![outline.clone(), folder("B", &[]), folder("B", &[])],
+  };
+
+  assert!(!twice.eq_unordered(&once));
+}
+
+#[test]
+fn test_canonicalize() {
+  let mut first = OPML::default();
+  first.body.outlines = vec![
+    folder("Feed", &[]),
+    folder("B", &[("2", "https://2"), ("1", "https://b")]),
+    folder("A", &[("1", "https://a"), ("1", "https://1")]),
+  ];
+
+  let mut second = OPML::default();
+  second.body.outlines = vec![
+    folder("A", &[("1", "https://1"), ("1", "https://a")]),
+    folder("B", &[("1", "https://b"), ("2", "https://2")]),
+    folder("Feed", &[]),
+  ];
+
+  first.canonicalize();
+  second.canonicalize();
+  assert_eq!(first.to_string().unwrap(), second.to_string().unwrap());
+
+  let texts = first
+    .body
+    .outlines
+    .iter()
+    .map(|outline| outline.text.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(texts, vec!["A", "B", "Feed"]);
+  assert_eq!(
+    first.body.outlines[0].outlines[0].xml_url.as_deref(),
+    Some("https://1")
+  );
+
+  let canonical = first.clone();
+  first.canonicalize();
+  assert_eq!(first, canonical);
+}
+
+#[test]
+fn test_canonicalize_tied_keys() {
+  let feed = |html_url: &str, children: &[(&str, &str)]| {
+    let mut outline = folder("Feed", children);
+    outline.xml_url = Some("https://example.com/feed".to_string());
+    outline.html_url = Some(html_url.to_string());
+    outline
+  };
+
+  let mut first = OPML::default();
+  first.body.outlines = vec![
+    feed("https://b", &[]),
+    feed("https://a", &[]),
+    feed("https://a", &[("2", "https://2"), ("1", "https://1")]),
+    feed("https://a", &[("1", "https://1"), ("3", "https://3")]),
+  ];
+
+  let mut second = first.clone();
+  second.body.outlines.reverse();
+  second.body.outlines[0].outlines.reverse();
+
+  first.canonicalize();
+  second.canonicalize();
+  assert_eq!(first.to_string().unwrap(), second.to_string().unwrap());
+  assert_eq!(
+    first.body.outlines[2].html_url.as_deref(),
+    Some("https://a")
+  );
+}
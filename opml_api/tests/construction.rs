@@ -59,3 +59,97 @@ fn test_opml_construction_2() -> Result<(), Box<dyn Error>> {
 
   Ok(())
 }
+
+#[test]
+fn test_body_push_feed_and_folder() -> Result<(), Box<dyn Error>> {
+  let mut opml = OPML {
+    head: Some(Head {
+      title: Some("Rust Feeds".to_string()),
+      ..Head::default()
+    }),
+    ..OPML::default()
+  };
+
+  opml
+    .body
+    .push_folder("Rust Feeds")
+    .add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml")
+    .add_feed(
+      "Inside Rust",
+      "https://blog.rust-lang.org/inside-rust/feed.xml",
+    );
+  opml
+    .body
+    .push_folder("Mozilla Feeds")
+    .add_feed("Mozilla Blog", "https://blog.mozilla.org/feed")
+    .add_feed("Mozilla Hacks", "https://hacks.mozilla.org/feed");
+
+  let expected = read("tests/samples/construction_2.opml")?;
+  assert_eq!(opml.to_string()?.trim(), expected.trim());
+
+  let feed = opml.body.push_feed("Feed", "https://example.com/feed.xml");
+  feed.language = Some("en".to_string());
+  assert_eq!(opml.body.outlines[2].language.as_deref(), Some("en"));
+
+  Ok(())
+}
+
+#[test]
+fn test_add_typed_feed() {
+  let mut opml = OPML::default();
+  opml
+    .add_typed_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml", "rss")
+    .add_feed("Untyped", "https://example.com/feed.xml");
+
+  assert_eq!(
+    opml.to_string().unwrap(),
+    concat!(
+      r#"<opml version="2.0"><head/><body>"#,
+      r#"<outline text="Rust Blog" type="rss" xmlUrl="https://blog.rust-lang.org/feed.xml"/>"#,
+      r#"<outline text="Untyped" xmlUrl="https://example.com/feed.xml"/>"#,
+      r#"</body></opml>"#
+    )
+  );
+}
+
+#[test]
+fn test_write_to_fmt() -> Result<(), Box<dyn Error>> {
+  struct Document(OPML);
+
+  impl std::fmt::Display for Document {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      self.0.write_to(f).map_err(|_| std::fmt::Error)
+    }
+  }
+
+  let mut opml = OPML::default();
+  opml.add_feed("Rust Blog", "https://blog.rust-lang.org/feed.xml");
+
+  let expected = opml.to_string()?;
+  assert_eq!(format!("{}", Document(opml)), expected);
+
+  Ok(())
+}
+
+#[test]
+fn test_body_to_string() -> Result<(), Box<dyn Error>> {
+  let opml = OPML::from_str(&read("tests/spec_samples/category.opml")?)?;
+
+  let body = opml.body.to_string()?;
+  let document = opml.to_string()?;
+  assert!(body.starts_with("<body>"));
+  assert!(document.contains(&body));
+
+  let pretty = opml.body.to_string_pretty()?;
+  assert!(pretty.contains("\n  <outline "));
+  let compact = pretty
+    .lines()
+    .map(|line| line.trim_start())
+    .collect::<String>();
+  assert_eq!(compact, body);
+
+  assert_eq!(Body::default().to_string()?, "<body/>");
+  assert_eq!(Body::default().to_string_pretty()?, "<body/>");
+
+  Ok(())
+}
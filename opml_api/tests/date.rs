@@ -0,0 +1,30 @@
+#![cfg(feature = "date")]
+
+use chrono::{DateTime, NaiveDateTime};
+use opml::*;
+
+#[test]
+fn test_to_writer_touching() {
+  let mut opml = OPML {
+    head: None,
+    ..OPML::default()
+  };
+  opml.add_feed("Feed", "https://example.com/feed.xml");
+
+  let mut writer = vec![];
+  opml.to_writer_touching(&mut writer).unwrap();
+
+  let date_modified = opml.head.as_ref().unwrap().date_modified.clone();
+  let date_modified = date_modified.unwrap();
+  let xml = String::from_utf8(writer).unwrap();
+  assert!(
+    xml.contains(&format!("<dateModified>{date_modified}</dateModified>"))
+  );
+
+  // The date is written in the format of the spec's examples, like
+  // `Mon, 27 Feb 2006 12:09:48 GMT`, and parses back as RFC822.
+  let format = "%a, %d %b %Y %H:%M:%S GMT";
+  assert!(NaiveDateTime::parse_from_str(&date_modified, format).is_ok());
+  assert!(DateTime::parse_from_rfc2822(&date_modified).is_ok());
+  assert!(opml.validate().is_empty());
+}
@@ -0,0 +1,203 @@
+use opml::*;
+
+/// Creates a document with two folders that each have two feeds.
+fn folders_document() -> OPML {
+  let mut opml = OPML::default();
+
+  for folder_text in ["A", "B"] {
+    let mut folder = Outline {
+      text: folder_text.to_string(),
+      ..Outline::default()
+    };
+    folder
+      .add_feed(&format!("{folder_text}1"), "https://example.com/1")
+      .add_feed(&format!("{folder_text}2"), "https://example.com/2");
+    opml.body.outlines.push(folder);
+  }
+
+  opml
+}
+
+fn texts(outlines: &[Outline]) -> Vec<&str> {
+  outlines
+    .iter()
+    .map(|outline| outline.text.as_str())
+    .collect()
+}
+
+#[test]
+fn test_move_outline_to_later_sibling() {
+  let mut opml = folders_document();
+  opml.move_outline(&[0], &[1]).unwrap();
+
+  assert_eq!(texts(&opml.body.outlines), vec!["B"]);
+  assert_eq!(
+    texts(&opml.body.outlines[0].outlines),
+    vec!["B1", "B2", "A"]
+  );
+  assert_eq!(opml.outline_at(&[0, 2, 1]).unwrap().text, "A2");
+}
+
+#[test]
+fn test_move_outline_to_body() {
+  let mut opml = folders_document();
+  opml.move_outline(&[1, 0], &[]).unwrap();
+
+  assert_eq!(texts(&opml.body.outlines), vec!["A", "B", "B1"]);
+  assert_eq!(texts(&opml.body.outlines[1].outlines), vec!["B2"]);
+}
+
+#[test]
+fn test_move_outline_errors() {
+  let mut opml = folders_document();
+  let original = opml.clone();
+
+  for (from, to_parent) in [
+    (vec![], vec![]),
+    (vec![2], vec![]),
+    (vec![0, 5], vec![1]),
+    (vec![0], vec![3]),
+    (vec![0], vec![0]),
+    (vec![0], vec![0, 1]),
+  ] {
+    let result = opml.move_outline(&from, &to_parent);
+    assert!(
+      matches!(result, Err(Error::InvalidMove(_))),
+      "{from:?} -> {to_parent:?}"
+    );
+  }
+
+  assert_eq!(opml, original);
+}
+
+#[test]
+fn test_export_folder() {
+  let mut opml = folders_document();
+  opml.head = Some(Head {
+    title: Some("Folders".to_string()),
+    ..Head::default()
+  });
+  opml.move_outline(&[1], &[0]).unwrap();
+
+  let exported = opml.export_folder("B").unwrap();
+  assert_eq!(exported.head, opml.head);
+  assert_eq!(texts(&exported.body.outlines), vec!["B1", "B2"]);
+
+  let exported = opml.export_folder("A").unwrap();
+  assert_eq!(texts(&exported.body.outlines), vec!["A1", "A2", "B"]);
+  assert_eq!(texts(&exported.body.outlines[2].outlines), vec!["B1", "B2"]);
+
+  // Feeds and empty folders aren't folders, even when their text matches.
+  opml.body.push_folder("Empty");
+  for (text, not_a_folder) in [("A1", true), ("Empty", true), ("C", false)] {
+    match opml.export_folder(text).unwrap_err() {
+      Error::NotAFolder(error_text) => {
+        assert!(not_a_folder);
+        assert_eq!(error_text, text);
+      }
+      Error::FolderNotFound(error_text) => {
+        assert!(!not_a_folder);
+        assert_eq!(error_text, text);
+      }
+      error => panic!("unexpected error: {error}"),
+    }
+  }
+
+  // A folder is found even when an outline before it has the same text.
+  opml
+    .body
+    .push_folder("Empty")
+    .add_feed("C1", "https://example.com/c");
+  let exported = opml.export_folder("Empty").unwrap();
+  assert_eq!(texts(&exported.body.outlines), vec!["C1"]);
+}
+
+#[test]
+fn test_append_outline() {
+  let mut opml = folders_document();
+  let outline = |text: &str| Outline {
+    text: text.to_string(),
+    ..Outline::default()
+  };
+
+  opml.append_outline(&[], outline("C")).unwrap();
+  opml.append_outline(&[0], outline("A3")).unwrap();
+  assert_eq!(texts(&opml.body.outlines), vec!["A", "B", "C"]);
+  assert_eq!(
+    texts(&opml.body.outlines[0].outlines),
+    vec!["A1", "A2", "A3"]
+  );
+
+  // Appending to a leaf turns it into a folder.
+  opml.append_outline(&[1, 0], outline("B1a")).unwrap();
+  assert_eq!(opml.outline_at(&[1, 0, 0]).unwrap().text, "B1a");
+
+  let error = opml.append_outline(&[3], outline("D")).unwrap_err();
+  assert!(matches!(error, Error::InvalidPath(_)));
+  assert_eq!(error.to_string(), "Invalid path: no parent outline at [3]");
+  assert!(opml.append_outline(&[0, 5], outline("D")).is_err());
+}
+
+#[test]
+fn test_retain_feeds() {
+  let mut opml = folders_document();
+  opml
+    .append_outline(
+      &[1],
+      Outline {
+        text: "Empty".to_string(),
+        outlines: vec![Outline {
+          text: "B3".to_string(),
+          xml_url: Some("https://example.com/3".to_string()),
+          ..Outline::default()
+        }],
+        ..Outline::default()
+      },
+    )
+    .unwrap();
+  opml
+    .append_outline(
+      &[],
+      Outline {
+        text: "Note".to_string(),
+        ..Outline::default()
+      },
+    )
+    .unwrap();
+
+  let removed = opml.retain_feeds(|feed| feed.text.starts_with('A'));
+  assert_eq!(removed, 3);
+
+  // "B" and its "Empty" subfolder are pruned, the "Note" isn't a folder.
+  assert_eq!(texts(&opml.body.outlines), vec!["A", "Note"]);
+  assert_eq!(texts(&opml.body.outlines[0].outlines), vec!["A1", "A2"]);
+
+  assert_eq!(opml.retain_feeds(|_| true), 0);
+  assert_eq!(opml.retain_feeds(|_| false), 2);
+  assert_eq!(texts(&opml.body.outlines), vec!["Note"]);
+
+  // Feeds nested in a removed feed are counted as removed too.
+  let mut opml = OPML::default();
+  opml
+    .body
+    .push_feed("Parent", "https://example.com/parent")
+    .add_feed("Child", "https://example.com/child");
+  assert_eq!(opml.retain_feeds(|feed| feed.text != "Parent"), 2);
+  assert!(opml.body.outlines.is_empty());
+}
+
+#[test]
+fn test_retain_top_level() {
+  let mut opml = folders_document();
+  opml.body.push_feed("C", "https://example.com/c");
+
+  let mut visited = vec![];
+  opml.body.retain_top_level(|outline| {
+    visited.push(outline.text.clone());
+    outline.text != "A"
+  });
+
+  assert_eq!(visited, vec!["A", "B", "C"]);
+  assert_eq!(texts(&opml.body.outlines), vec!["B", "C"]);
+  assert_eq!(texts(&opml.body.outlines[0].outlines), vec!["B1", "B2"]);
+}
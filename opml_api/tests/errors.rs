@@ -16,9 +16,65 @@ fn test_invalid_opml_version() {
   assert!(matches!(res, Err(Error::UnsupportedVersion(e)) if e == "invalid"));
 }
 
+#[test]
+fn test_invalid_opml_version_with_whitespace() {
+  let xml = r#"<opml version=" 3.0 "><head/><body><outline text="Outline"/></body></opml>"#;
+  let res = OPML::from_str(xml);
+  assert!(matches!(res, Err(Error::UnsupportedVersion(e)) if e == "3.0"));
+}
+
 #[test]
 fn test_invalid_opml_no_outlines() {
   let sample = read("tests/samples/invalid_opml_no_outlines.opml").unwrap();
   let res = OPML::from_str(&sample);
   assert!(matches!(res, Err(Error::BodyHasNoOutlines)));
 }
+
+#[test]
+fn test_invalid_head_number() {
+  let xml = r#"<opml version="2.0"><head><windowTop>top</windowTop></head><body><outline text="Outline"/></body></opml>"#;
+  let res = OPML::from_str(xml);
+  assert!(matches!(res, Err(Error::XmlError(_))));
+}
+
+#[test]
+fn test_from_str_multi_invalid_document() {
+  let xml = concat!(
+    r#"<opml version="2.0"><head/><body><outline text="A"/></body></opml>"#,
+    "\n",
+    r#"<opml version="3.0"><head/><body><outline text="B"/></body></opml>"#,
+  );
+
+  let error = OPML::from_str_multi(xml).unwrap_err();
+  assert!(matches!(
+    &error,
+    Error::InvalidDocument { index: 1, source }
+      if matches!(**source, Error::UnsupportedVersion(_))
+  ));
+  assert_eq!(error.to_string(), "Invalid document at index 1");
+  assert_eq!(
+    std::error::Error::source(&error).unwrap().to_string(),
+    r#"Unsupported OPML version: "3.0""#
+  );
+}
+
+#[test]
+fn test_invalid_utf8() {
+  let mut bytes = read("tests/samples/minimum_valid_opml.opml")
+    .unwrap()
+    .into_bytes();
+  let offset = bytes.windows(7).position(|w| w == b"Outline").unwrap();
+  bytes[offset + 3] = 0xC3;
+
+  let res = OPML::from_bytes(&bytes);
+  assert!(matches!(res, Err(Error::Encoding { offset: o }) if o == offset + 3));
+
+  // The offset includes the byte order mark.
+  let with_bom = [b"\xEF\xBB\xBF".as_slice(), &bytes].concat();
+  let res = OPML::from_reader(&mut with_bom.as_slice());
+  assert!(matches!(res, Err(Error::Encoding { offset: o }) if o == offset + 6));
+  assert_eq!(
+    res.unwrap_err().to_string(),
+    format!("Invalid UTF-8 at byte {}", offset + 6)
+  );
+}
@@ -0,0 +1,66 @@
+use std::fs::read_to_string as read;
+
+use opml::*;
+
+fn texts<'a>(outlines: &[&'a Outline]) -> Vec<&'a str> {
+  outlines
+    .iter()
+    .map(|outline| outline.text.as_str())
+    .collect()
+}
+
+#[test]
+fn test_expanded_outlines_everything_sample() {
+  let document = OPML::from_str(
+    &read("tests/samples/valid_opml_with_everything.opml").unwrap(),
+  )
+  .unwrap();
+
+  // The expansion state is "0,1", lines start at 1 so 0 is ignored.
+  assert_eq!(texts(&document.expanded_outlines()), vec!["Outline Text"]);
+}
+
+#[test]
+fn test_expanded_outlines_places_lived() {
+  let document =
+    OPML::from_str(&read("tests/spec_samples/placesLived.opml").unwrap())
+      .unwrap();
+
+  assert_eq!(
+    texts(&document.expanded_outlines()),
+    vec![
+      "Places I've lived",
+      "Boston",
+      "Bay Area",
+      "New Orleans",
+      "Wisconsin",
+      "Florida"
+    ]
+  );
+}
+
+#[test]
+fn test_expanded_outlines_only_counts_visible_lines() {
+  let mut document = OPML::default();
+  let mut first = Outline {
+    text: "First".to_string(),
+    ..Outline::default()
+  };
+  first.add_feed("Hidden", "https://example.com/hidden");
+
+  document.body.outlines.push(first);
+  document.add_feed("Second", "https://example.com/second");
+  document.head = Some(Head {
+    expansion_state: Some("2, 7, invalid".to_string()),
+    ..Head::default()
+  });
+
+  // "First" isn't expanded so its child isn't a visible line.
+  assert_eq!(texts(&document.expanded_outlines()), vec!["Second"]);
+}
+
+#[test]
+fn test_expanded_outlines_without_state() {
+  let document = OPML::default();
+  assert!(document.expanded_outlines().is_empty());
+}
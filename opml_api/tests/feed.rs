@@ -0,0 +1,43 @@
+#![cfg(feature = "feed-rs")]
+
+use opml::Outline;
+
+#[test]
+fn test_from_atom_feed() {
+  let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Inside Rust</title>
+  <subtitle>Want to follow along with Rust development?</subtitle>
+  <link rel="self" href="https://blog.rust-lang.org/inside-rust/feed.xml"/>
+  <link rel="alternate" href="https://blog.rust-lang.org/inside-rust/"/>
+  <id>https://blog.rust-lang.org/inside-rust/</id>
+  <updated>2024-01-01T00:00:00Z</updated>
+</feed>"#;
+
+  let feed = feed_rs::parser::parse(xml.as_bytes()).unwrap();
+  let xml_url = "https://blog.rust-lang.org/inside-rust/feed.xml";
+
+  assert_eq!(
+    Outline::from_feed(&feed, xml_url),
+    Outline {
+      text: "Inside Rust".to_string(),
+      r#type: Some("rss".to_string()),
+      xml_url: Some(xml_url.to_string()),
+      description: Some(
+        "Want to follow along with Rust development?".to_string()
+      ),
+      html_url: Some("https://blog.rust-lang.org/inside-rust/".to_string()),
+      title: Some("Inside Rust".to_string()),
+      ..Outline::default()
+    }
+  );
+}
+
+#[test]
+fn test_from_feed_without_title() {
+  let xml = r#"<rss version="2.0"><channel></channel></rss>"#;
+  let feed = feed_rs::parser::parse(xml.as_bytes()).unwrap();
+  let outline = Outline::from_feed(&feed, "https://example.com/feed.xml");
+
+  assert_eq!(outline.text, "https://example.com/feed.xml");
+  assert_eq!(outline.title, None);
+}
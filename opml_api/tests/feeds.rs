@@ -0,0 +1,164 @@
+use opml::*;
+
+#[test]
+fn test_find_feed_normalization() {
+  let mut folder = Outline {
+    text: "Folder".to_string(),
+    ..Outline::default()
+  };
+  folder.add_feed("Nested", "HTTPS://Example.com/Feed/");
+
+  let mut opml = OPML::default();
+  opml.body.outlines.push(folder);
+
+  let feed = opml.find_feed(" https://example.com/Feed ").unwrap();
+  assert_eq!(feed.text, "Nested");
+
+  // Paths are case-sensitive.
+  assert!(opml.find_feed("https://example.com/feed").is_none());
+}
+
+#[test]
+fn test_missing_feeds_nested() {
+  let mut subscriptions = OPML::default();
+  subscriptions.add_feed("A", "https://a.example.com/");
+
+  let mut folder = Outline {
+    text: "Folder".to_string(),
+    ..Outline::default()
+  };
+  folder
+    .add_feed("A", "https://A.example.com")
+    .add_feed("B", "https://b.example.com/");
+
+  let mut reference = OPML::default();
+  reference.body.outlines.push(folder);
+
+  let missing = subscriptions.missing_feeds(&reference);
+  assert_eq!(missing, vec![&reference.body.outlines[0].outlines[1]]);
+}
+
+#[test]
+fn test_replace_feed_url_nested() {
+  let mut folder = Outline {
+    text: "Folder".to_string(),
+    html_url: Some("https://old.example.com/".to_string()),
+    ..Outline::default()
+  };
+  folder
+    .add_feed("A", "https://old.example.com")
+    .add_feed("B", "https://other.example.com");
+
+  let mut opml = OPML::default();
+  opml.body.outlines.push(folder);
+  opml.add_feed("A", "https://OLD.example.com/");
+
+  assert_eq!(
+    opml.replace_feed_url("https://old.example.com", "https://new"),
+    2
+  );
+  assert_eq!(
+    opml.outline_at(&[0, 0]).unwrap().xml_url.as_deref(),
+    Some("https://new")
+  );
+  assert_eq!(
+    opml.outline_at(&[1]).unwrap().xml_url.as_deref(),
+    Some("https://new")
+  );
+  assert_eq!(
+    opml.outline_at(&[0, 1]).unwrap().xml_url.as_deref(),
+    Some("https://other.example.com")
+  );
+
+  // The folder's html_url is only replaced with replace_url.
+  assert_eq!(
+    opml.replace_feed_url("https://old.example.com", "https://new"),
+    0
+  );
+  assert_eq!(
+    opml.replace_url("https://old.example.com", "https://new"),
+    1
+  );
+  assert_eq!(
+    opml.outline_at(&[0]).unwrap().html_url.as_deref(),
+    Some("https://new")
+  );
+}
+
+#[test]
+fn test_feed_pairs() {
+  let mut folder = Outline {
+    text: "Folder".to_string(),
+    html_url: Some("https://example.com/".to_string()),
+    ..Outline::default()
+  };
+  folder.outlines.push(Outline {
+    title: Some("Titled Feed".to_string()),
+    xml_url: Some("https://example.com/titled.xml".to_string()),
+    ..Outline::default()
+  });
+  folder.outlines.push(Outline {
+    text: " ".to_string(),
+    xml_url: Some("https://example.com/untitled.xml".to_string()),
+    ..Outline::default()
+  });
+
+  let mut opml = OPML::default();
+  opml.body.outlines.push(folder);
+  opml.add_feed("Feed", "https://example.com/feed.xml");
+
+  let pair = |name: &str, url: &str| (name.to_string(), url.to_string());
+  assert_eq!(
+    opml.feed_pairs(),
+    vec![
+      pair("Titled Feed", "https://example.com/titled.xml"),
+      pair(
+        "https://example.com/untitled.xml",
+        "https://example.com/untitled.xml"
+      ),
+      pair("Feed", "https://example.com/feed.xml"),
+    ]
+  );
+}
+
+#[test]
+fn test_incomplete_feeds() {
+  let outline =
+    |text: &str, r#type: Option<&str>, xml_url: Option<&str>| Outline {
+      text: text.to_string(),
+      r#type: r#type.map(str::to_string),
+      xml_url: xml_url.map(str::to_string),
+      ..Outline::default()
+    };
+
+  let mut opml = OPML::default();
+  opml.body.outlines = vec![
+    outline("Complete", Some("rss"), Some(" https://example.com/feed ")),
+    outline("Plain Folder", None, None),
+    outline("  ", None, Some("https://example.com/feed")),
+    outline("No URL", Some("RSS"), None),
+    outline("Not a URL", None, Some("example.com/feed")),
+    Outline {
+      url: Some("https://example.com/".to_string()),
+      ..outline("Link", Some("link"), None)
+    },
+    Outline {
+      outlines: vec![
+        outline(
+          "Nested Link",
+          Some("link"),
+          Some("https://example.com/feed"),
+        ),
+        outline("Nested Complete", None, Some("https://example.org/feed")),
+      ],
+      ..outline("Folder", None, None)
+    },
+  ];
+
+  let texts = opml
+    .incomplete_feeds()
+    .into_iter()
+    .map(|outline| outline.text.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(texts, ["  ", "No URL", "Not a URL", "Nested Link"]);
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use opml::OPML;
+
+/// Generates deterministic pseudo-random bytes so the test is reproducible.
+fn pseudo_random_bytes(seed: u64, length: usize) -> Vec<u8> {
+  let mut state = seed;
+
+  (0..length)
+    .map(|_| {
+      state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+      (state >> 56) as u8
+    })
+    .collect()
+}
+
+#[test]
+fn test_arbitrary_round_trip() {
+  for seed in 0..256 {
+    let bytes = pseudo_random_bytes(seed, 4096);
+    let mut u = Unstructured::new(&bytes);
+    let document = OPML::arbitrary(&mut u).unwrap();
+
+    let xml = document.to_string().unwrap();
+    assert_eq!(OPML::from_str(&xml).unwrap(), document, "seed {seed}");
+  }
+}
+
+#[test]
+fn test_arbitrary_from_empty_data() {
+  let mut u = Unstructured::new(&[]);
+  let document = OPML::arbitrary(&mut u).unwrap();
+
+  assert!(!document.body.outlines.is_empty());
+  assert!(OPML::from_str(&document.to_string().unwrap()).is_ok());
+}
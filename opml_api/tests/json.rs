@@ -0,0 +1,94 @@
+use opml::*;
+
+fn document() -> OPML {
+  let mut opml = OPML {
+    head: Some(Head {
+      date_created: Some("Mon, 27 Feb 2006 12:09:48 GMT".to_string()),
+      window_top: Some(10),
+      ..Head::default()
+    }),
+    ..OPML::default()
+  };
+  opml.body.outlines.push(Outline {
+    text: "Rust Blog".to_string(),
+    is_comment: Some(false),
+    xml_url: Some("https://blog.rust-lang.org/feed.xml".to_string()),
+    html_url: Some("https://blog.rust-lang.org/".to_string()),
+    ..Outline::default()
+  });
+  opml
+}
+
+#[cfg(feature = "serde-camel-case")]
+#[test]
+fn test_json_camel_case_round_trip() {
+  let opml = document();
+  let json = serde_json::to_string(&opml).unwrap();
+
+  for key in [
+    "\"dateCreated\"",
+    "\"windowTop\"",
+    "\"isComment\"",
+    "\"xmlUrl\"",
+    "\"htmlUrl\"",
+  ] {
+    assert!(json.contains(key), "{key} missing from {json}");
+  }
+  assert!(!json.contains("xml_url"));
+
+  assert_eq!(serde_json::from_str::<OPML>(&json).unwrap(), opml);
+}
+
+#[cfg(feature = "serde-camel-case")]
+#[test]
+fn test_json_camel_case_snake_case_aliases() {
+  let json = r#"{
+    "version": "2.0",
+    "head": {
+      "title": null,
+      "date_created": "Mon, 27 Feb 2006 12:09:48 GMT",
+      "date_modified": null,
+      "owner_name": null,
+      "owner_email": null,
+      "owner_id": null,
+      "docs": null,
+      "expansion_state": null,
+      "vert_scroll_state": null,
+      "window_top": 10,
+      "window_left": null,
+      "window_bottom": null,
+      "window_right": null
+    },
+    "body": {
+      "outlines": [{
+        "text": "Rust Blog",
+        "type": null,
+        "is_comment": false,
+        "is_breakpoint": null,
+        "created": null,
+        "category": null,
+        "outlines": [],
+        "xml_url": "https://blog.rust-lang.org/feed.xml",
+        "description": null,
+        "html_url": "https://blog.rust-lang.org/",
+        "language": null,
+        "title": null,
+        "version": null,
+        "url": null
+      }]
+    }
+  }"#;
+
+  assert_eq!(serde_json::from_str::<OPML>(json).unwrap(), document());
+}
+
+#[cfg(not(feature = "serde-camel-case"))]
+#[test]
+fn test_json_snake_case_round_trip() {
+  let opml = document();
+  let json = serde_json::to_string(&opml).unwrap();
+
+  assert!(json.contains("\"xml_url\""));
+  assert!(json.contains("\"date_created\""));
+  assert_eq!(serde_json::from_str::<OPML>(&json).unwrap(), opml);
+}
@@ -0,0 +1,300 @@
+use std::fs::read_to_string as read;
+
+use opml::*;
+
+#[test]
+fn test_case_insensitive_attributes() {
+  let sample = read("tests/samples/case_insensitive_attributes.opml").unwrap();
+  let options = ParseOptions {
+    case_insensitive_attributes: true,
+    ..ParseOptions::default()
+  };
+  let document = OPML::from_str_with_options(&sample, &options).unwrap();
+
+  let mut expected_folder = Outline {
+    text: "Folder".to_string(),
+    is_comment: Some(false),
+    ..Outline::default()
+  };
+  expected_folder.outlines.push(Outline {
+    text: "Rust Blog".to_string(),
+    r#type: Some("rss".to_string()),
+    xml_url: Some("https://blog.rust-lang.org/feed.xml".to_string()),
+    html_url: Some("https://blog.rust-lang.org/".to_string()),
+    ..Outline::default()
+  });
+
+  assert_eq!(document.body.outlines, vec![expected_folder]);
+}
+
+#[test]
+fn test_case_sensitive_attributes_by_default() {
+  let sample = read("tests/samples/case_insensitive_attributes.opml").unwrap();
+  let document = OPML::from_str(&sample).unwrap();
+
+  let folder = &document.body.outlines[0];
+  assert_eq!(folder.text, "");
+  assert_eq!(folder.is_comment, None);
+  assert_eq!(folder.outlines[0].xml_url, None);
+  assert_eq!(folder.outlines[0].html_url, None);
+}
+
+#[test]
+fn test_max_outlines() {
+  // The sample has five outlines, three of them nested.
+  let sample = read("tests/samples/max_outlines.opml").unwrap();
+  assert_eq!(OPML::from_str(&sample).unwrap().outlines().count(), 5);
+
+  let options = ParseOptions {
+    max_outlines: Some(5),
+    ..ParseOptions::default()
+  };
+  assert!(OPML::from_str_with_options(&sample, &options).is_ok());
+
+  let options = ParseOptions {
+    max_outlines: Some(4),
+    ..ParseOptions::default()
+  };
+  let error = OPML::from_str_with_options(&sample, &options).unwrap_err();
+  assert!(matches!(error, Error::TooManyOutlines(4)));
+  assert_eq!(error.to_string(), "OPML document has more than 4 outlines");
+}
+
+#[test]
+fn test_write_options_omit_head_fields() {
+  let document = OPML {
+    head: Some(Head {
+      title: Some("Feeds".to_string()),
+      owner_name: Some("Owner".to_string()),
+      owner_email: Some("owner@example.com".to_string()),
+      owner_id: Some("https://example.com/".to_string()),
+      window_top: Some(10),
+      ..Head::default()
+    }),
+    ..OPML::default()
+  };
+
+  assert_eq!(
+    document
+      .to_string_with_options(&WriteOptions::redact_owner())
+      .unwrap(),
+    r#"<opml version="2.0"><head><title>Feeds</title><windowTop>10</windowTop></head><body/></opml>"#
+  );
+
+  let options = WriteOptions {
+    omit_head_fields: vec![HeadField::Title, HeadField::WindowTop],
+    ..WriteOptions::default()
+  };
+  assert_eq!(
+    document.to_string_with_options(&options).unwrap(),
+    concat!(
+      r#"<opml version="2.0"><head><ownerName>Owner</ownerName>"#,
+      r#"<ownerEmail>owner@example.com</ownerEmail>"#,
+      r#"<ownerId>https://example.com/</ownerId></head><body/></opml>"#
+    )
+  );
+
+  // The document itself is left untouched.
+  let head = document.head.as_ref().unwrap();
+  assert_eq!(head.owner_email.as_deref(), Some("owner@example.com"));
+  assert_eq!(
+    document
+      .to_string_with_options(&WriteOptions::default())
+      .unwrap(),
+    document.to_string().unwrap()
+  );
+}
+
+#[test]
+fn test_write_options_single_quote_attributes() {
+  let mut folder = Outline {
+    text: "It's \"quoted\" & <escaped>".to_string(),
+    is_comment: Some(false),
+    ..Outline::default()
+  };
+  folder.add_feed("Feed", "https://example.com/?a=1&b=2");
+
+  let mut document = OPML {
+    head: None,
+    ..OPML::default()
+  };
+  document.body.outlines.push(folder);
+
+  let options = WriteOptions {
+    single_quote_attributes: true,
+    ..WriteOptions::default()
+  };
+  let xml = document.to_string_with_options(&options).unwrap();
+  assert_eq!(
+    xml,
+    concat!(
+      "<opml version='2.0'><body>",
+      "<outline text='It&apos;s &quot;quoted&quot; &amp; &lt;escaped&gt;' isComment='false'>",
+      "<outline text='Feed' xmlUrl='https://example.com/?a=1&amp;b=2'/>",
+      "</outline></body></opml>"
+    )
+  );
+  assert_eq!(OPML::from_str(&xml).unwrap(), document);
+
+  let xml = document.to_string().unwrap();
+  assert!(xml.starts_with(
+    r#"<opml version="2.0"><body><outline text="It&apos;s &quot;quoted&quot;"#
+  ));
+  assert_eq!(OPML::from_str(&xml).unwrap(), document);
+}
+
+#[test]
+fn test_source_encoding_declaration() {
+  let sample = read("tests/spec_samples/states.opml").unwrap();
+  let document = OPML::from_str(&sample).unwrap();
+  assert_eq!(document.source_encoding.as_deref(), Some("ISO-8859-1"));
+
+  let options = WriteOptions {
+    xml_declaration: true,
+    ..WriteOptions::default()
+  };
+  // The written XML is UTF-8, so that's what's declared.
+  let xml = document.to_string_with_options(&options).unwrap();
+  assert!(xml.starts_with(
+    r#"<?xml version="1.0" encoding="UTF-8"?><opml version="2.0">"#
+  ));
+
+  // The source encoding isn't part of the document's content.
+  let written = OPML::from_str(&xml).unwrap();
+  assert_eq!(written.source_encoding.as_deref(), Some("UTF-8"));
+  assert_eq!(written, document);
+
+  // Nothing is declared unless asked for.
+  let document = OPML::default();
+  assert_eq!(document.source_encoding, None);
+  assert_eq!(
+    document.to_string_with_options(&options).unwrap(),
+    r#"<?xml version="1.0" encoding="UTF-8"?><opml version="2.0"><head/><body/></opml>"#
+  );
+  assert!(document.to_string().unwrap().starts_with("<opml"));
+}
+
+#[test]
+fn test_write_options_declared_encoding() {
+  let sample = read("tests/spec_samples/states.opml").unwrap();
+  let document = OPML::from_str(&sample).unwrap();
+
+  let options = WriteOptions {
+    declared_encoding: document.source_encoding.clone(),
+    xml_declaration: true,
+    ..WriteOptions::default()
+  };
+  let xml = document.to_string_with_options(&options).unwrap();
+  assert!(xml.starts_with(
+    r#"<?xml version="1.0" encoding="ISO-8859-1"?><opml version="2.0">"#
+  ));
+
+  // The declaration is read back, the content is unchanged.
+  let written = OPML::from_str(&xml).unwrap();
+  assert_eq!(written.source_encoding.as_deref(), Some("ISO-8859-1"));
+  assert_eq!(written, document);
+
+  // Without a declaration there is nothing to put the encoding in.
+  let options = WriteOptions {
+    declared_encoding: Some("ISO-8859-1".to_string()),
+    ..WriteOptions::default()
+  };
+  let xml = document.to_string_with_options(&options).unwrap();
+  assert!(xml.starts_with("<opml"));
+}
+
+#[test]
+fn test_write_options_omit_invalid_head_fields() {
+  let options = WriteOptions {
+    omit_invalid_head_fields: true,
+    ..WriteOptions::default()
+  };
+
+  for (owner_email, owner_id, expected) in [
+    (
+      "owner@example.com",
+      "https://example.com/",
+      "<ownerEmail>owner@example.com</ownerEmail><ownerId>https://example.com/</ownerId>",
+    ),
+    ("", "example.com", ""),
+    ("owner", " ", ""),
+    ("owner@", "https://example.com/", "<ownerId>https://example.com/</ownerId>"),
+    ("owner@example.com", "", "<ownerEmail>owner@example.com</ownerEmail>"),
+  ] {
+    let document = OPML {
+      head: Some(Head {
+        owner_email: Some(owner_email.to_string()),
+        owner_id: Some(owner_id.to_string()),
+        ..Head::default()
+      }),
+      ..OPML::default()
+    };
+
+    let expected_head = if expected.is_empty() {
+      "<head/>".to_string()
+    } else {
+      format!("<head>{expected}</head>")
+    };
+    assert_eq!(
+      document.to_string_with_options(&options).unwrap(),
+      format!(r#"<opml version="2.0">{expected_head}<body/></opml>"#),
+      "{owner_email:?} {owner_id:?}"
+    );
+  }
+}
+
+#[test]
+fn test_to_bytes() {
+  let mut document = OPML::default();
+  document.add_feed("Feed", "https://example.com/feed.xml");
+
+  let bytes = document.to_bytes().unwrap();
+  assert_eq!(bytes, document.to_string().unwrap().into_bytes());
+
+  let options = WriteOptions {
+    byte_order_mark: true,
+    ..WriteOptions::default()
+  };
+  let bytes = document.to_bytes_with_options(&options).unwrap();
+  assert_eq!(&bytes[..3], b"\xEF\xBB\xBF");
+  assert_eq!(&bytes[3..], document.to_bytes().unwrap());
+  assert_eq!(OPML::from_bytes(&bytes).unwrap(), document);
+}
+
+#[test]
+fn test_record_original_index() {
+  let xml = r#"<opml version="2.0"><head/><body><outline text="A"><outline text="A1"/><outline text="A2"/></outline><outline text="B"/></body></opml>"#;
+
+  let document = OPML::from_str(xml).unwrap();
+  assert!(document
+    .outlines()
+    .all(|outline| outline.original_index.is_none()));
+
+  let options = ParseOptions {
+    record_original_index: true,
+    ..ParseOptions::default()
+  };
+  let mut document = OPML::from_str_with_options(xml, &options).unwrap();
+  let indices = document
+    .outlines()
+    .map(|outline| (outline.text.as_str(), outline.original_index))
+    .collect::<Vec<_>>();
+  assert_eq!(
+    indices,
+    [
+      ("A", Some(0)),
+      ("A1", Some(0)),
+      ("A2", Some(1)),
+      ("B", Some(1))
+    ]
+  );
+  assert_eq!(document.to_string().unwrap(), xml);
+  assert_eq!(document, OPML::from_str(xml).unwrap());
+
+  document.body.outlines.reverse();
+  document
+    .body
+    .outlines
+    .sort_by_key(|outline| outline.original_index);
+  assert_eq!(document.to_string().unwrap(), xml);
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+  fmt::Debug,
+  sync::{Arc, Mutex},
+};
+
+use opml::*;
+use tracing::{
+  field::{Field, Visit},
+  span, Event, Metadata, Subscriber,
+};
+
+/// The fields of a recorded span or event, formatted with [`Debug`].
+type Fields = Vec<(String, String)>;
+
+/// A subscriber recording the fields of all spans and events.
+#[derive(Clone, Default)]
+struct Recorder {
+  spans: Arc<Mutex<Vec<(String, Fields)>>>,
+  events: Arc<Mutex<Vec<Fields>>>,
+}
+
+/// Collects the fields it visits.
+struct FieldVisitor<'a>(&'a mut Fields);
+
+impl Visit for FieldVisitor<'_> {
+  fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+    self
+      .0
+      .push((field.name().to_string(), format!("{value:?}")));
+  }
+}
+
+impl Subscriber for Recorder {
+  fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+    true
+  }
+
+  fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+    let mut fields = vec![];
+    span.record(&mut FieldVisitor(&mut fields));
+
+    let mut spans = self.spans.lock().unwrap();
+    spans.push((span.metadata().name().to_string(), fields));
+    span::Id::from_u64(spans.len() as u64)
+  }
+
+  fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+  fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+  fn event(&self, event: &Event<'_>) {
+    let mut fields = vec![];
+    event.record(&mut FieldVisitor(&mut fields));
+    self.events.lock().unwrap().push(fields);
+  }
+
+  fn enter(&self, _span: &span::Id) {}
+
+  fn exit(&self, _span: &span::Id) {}
+}
+
+/// Parses the XML while recording, returning the span and event fields.
+fn record_parse(xml: &str) -> ((String, Fields), Fields) {
+  let recorder = Recorder::default();
+  tracing::subscriber::with_default(recorder.clone(), || {
+    let _ = OPML::from_str(xml);
+  });
+
+  let mut spans = recorder.spans.lock().unwrap().clone();
+  let mut events = recorder.events.lock().unwrap().clone();
+  assert_eq!((spans.len(), events.len()), (1, 1));
+  (spans.remove(0), events.remove(0))
+}
+
+/// Returns the names of the fields and their values, leaving out the duration
+/// since it changes between runs.
+fn without_duration(fields: &Fields) -> Vec<(&str, &str)> {
+  fields
+    .iter()
+    .filter(|(name, _)| name != "duration_us")
+    .map(|(name, value)| (name.as_str(), value.as_str()))
+    .collect()
+}
+
+#[test]
+fn test_tracing_parsed() {
+  let xml = r#"<opml version="2.0"><head/><body><outline text="Feed" xmlUrl="https://example.com/"/></body></opml>"#;
+  let ((name, span_fields), event_fields) = record_parse(xml);
+
+  assert_eq!(name, "parse_opml");
+  assert_eq!(
+    span_fields,
+    vec![("size".to_string(), xml.len().to_string())]
+  );
+  assert!(event_fields.iter().any(|(name, _)| name == "duration_us"));
+  assert_eq!(
+    without_duration(&event_fields),
+    vec![
+      ("message", "parsed OPML document"),
+      ("version", "2.0"),
+      ("feeds", "1"),
+    ]
+  );
+}
+
+#[test]
+fn test_tracing_failed() {
+  // The unsupported version is text from the document, so it isn't recorded.
+  let xml = r#"<opml version="secret"><head/><body/></opml>"#;
+  let ((name, _), event_fields) = record_parse(xml);
+
+  assert_eq!(name, "parse_opml");
+  assert!(event_fields.iter().any(|(name, _)| name == "duration_us"));
+  assert_eq!(
+    without_duration(&event_fields),
+    vec![
+      ("message", "failed to parse OPML document"),
+      ("error", "\"UnsupportedVersion\""),
+    ]
+  );
+  assert!(!event_fields
+    .iter()
+    .any(|(_, value)| value.contains("secret")));
+}
@@ -0,0 +1,219 @@
+use opml::*;
+
+fn nested_document() -> OPML {
+  let mut opml = OPML::default();
+
+  let mut group = Outline {
+    text: "Group".to_string(),
+    ..Outline::default()
+  };
+  group.add_feed("Nested Feed", "http://example.com/nested");
+
+  opml.body.outlines.push(group);
+  opml.add_feed("Feed", "http://example.com/feed");
+  opml
+}
+
+#[test]
+fn test_map_outlines_mut_order() {
+  let mut opml = nested_document();
+  let mut visited = vec![];
+
+  opml.map_outlines_mut(|outline| visited.push(outline.text.clone()));
+
+  assert_eq!(visited, vec!["Group", "Nested Feed", "Feed"]);
+}
+
+#[test]
+fn test_map_outlines_mut_visits_added_children() {
+  let mut opml = nested_document();
+  let mut visited = vec![];
+
+  opml.map_outlines_mut(|outline| {
+    if outline.text == "Feed" {
+      outline.add_feed("Added Feed", "http://example.com/added");
+    }
+
+    visited.push(outline.text.clone());
+  });
+
+  assert_eq!(visited, vec!["Group", "Nested Feed", "Feed", "Added Feed"]);
+}
+
+#[test]
+fn test_outlines_order() {
+  let mut opml = nested_document();
+  opml.body.outlines[0].outlines[0].add_feed("Deep", "http://example.com/deep");
+
+  let texts = opml
+    .outlines()
+    .map(|outline| outline.text.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(texts, vec!["Group", "Nested Feed", "Deep", "Feed"]);
+}
+
+#[test]
+fn test_any_outline_short_circuits() {
+  let opml = nested_document();
+  let mut visited = 0;
+
+  assert!(opml.any_outline(|outline| {
+    visited += 1;
+    outline.xml_url.is_some()
+  }));
+  assert_eq!(visited, 2);
+}
+
+#[test]
+fn test_all_outlines_short_circuits() {
+  let opml = nested_document();
+  let mut visited = 0;
+
+  assert!(!opml.all_outlines(|outline| {
+    visited += 1;
+    outline.xml_url.is_some()
+  }));
+  assert_eq!(visited, 1);
+  assert!(OPML::default().all_outlines(|_| false));
+}
+
+#[test]
+fn test_is_flat() {
+  assert!(!nested_document().is_flat());
+  assert!(OPML::default().is_flat());
+
+  let mut opml = OPML::default();
+  opml.add_feed("Feed", "https://example.com/feed.xml");
+  opml.body.push_folder("Empty Folder");
+  assert!(opml.is_flat());
+}
+
+#[test]
+fn test_descendant_counts() {
+  let mut opml = nested_document();
+  let group = &mut opml.body.outlines[0];
+  group.outlines.push(Outline {
+    text: "Subgroup".to_string(),
+    ..Outline::default()
+  });
+  group.outlines[1].add_feed("Deep Feed", "http://example.com/deep");
+
+  let group = &opml.body.outlines[0];
+  assert_eq!(group.descendant_count(), 3);
+  assert_eq!(group.descendant_feed_count(), 2);
+  assert_eq!(group.outlines[1].descendant_count(), 1);
+
+  let feed = &opml.body.outlines[1];
+  assert_eq!(feed.descendant_count(), 0);
+  assert_eq!(feed.descendant_feed_count(), 0);
+}
+
+#[test]
+fn test_feeds() {
+  let opml = nested_document();
+  let texts = opml.feeds().map(|feed| &feed.text).collect::<Vec<_>>();
+  assert_eq!(texts, vec!["Nested Feed", "Feed"]);
+}
+
+#[test]
+fn test_feeds_mut_skips_children_of_feeds() {
+  let mut opml = nested_document();
+  opml.body.outlines[1].add_feed("Child Feed", "http://example.com/child");
+
+  for feed in opml.feeds_mut() {
+    feed.text.push_str(" (updated)");
+  }
+
+  let texts = opml.outlines().map(|feed| &feed.text).collect::<Vec<_>>();
+  assert_eq!(
+    texts,
+    vec![
+      "Group",
+      "Nested Feed (updated)",
+      "Feed (updated)",
+      "Child Feed"
+    ]
+  );
+}
+
+#[test]
+fn test_siblings() {
+  let opml = nested_document();
+
+  let top_level = opml.siblings(&[1]).unwrap();
+  assert_eq!(top_level, opml.body.outlines.as_slice());
+  assert_eq!(opml.siblings(&[0]).unwrap().len(), 2);
+
+  let nested = opml.siblings(&[0, 0]).unwrap();
+  assert_eq!(nested.len(), 1);
+  assert_eq!(nested[0].text, "Nested Feed");
+
+  assert_eq!(opml.siblings(&[]), None);
+  assert_eq!(opml.siblings(&[2]), None);
+  assert_eq!(opml.siblings(&[0, 1]), None);
+  assert_eq!(opml.siblings(&[1, 0]), None);
+}
+
+#[test]
+fn test_partition_by_type() {
+  let mut opml = nested_document();
+  opml.body.outlines[0].r#type = Some("folder".to_string());
+  opml.body.outlines[1].r#type = Some("rss".to_string());
+  opml.body.outlines[0].outlines.push(Outline {
+    text: "Link".to_string(),
+    r#type: Some("link".to_string()),
+    url: Some("http://example.com/".to_string()),
+    ..Outline::default()
+  });
+  opml.body.outlines[0].add_feed("Untyped Feed", "http://example.com/other");
+
+  let groups = opml.partition_by_type();
+  let texts = |key: Option<&str>| {
+    groups[&key.map(str::to_string)]
+      .iter()
+      .map(|outline| outline.text.as_str())
+      .collect::<Vec<_>>()
+  };
+
+  assert_eq!(groups.len(), 4);
+  assert_eq!(texts(Some("folder")), vec!["Group"]);
+  assert_eq!(texts(Some("rss")), vec!["Feed"]);
+  assert_eq!(texts(Some("link")), vec!["Link"]);
+  assert_eq!(texts(None), vec!["Nested Feed", "Untyped Feed"]);
+}
+
+#[test]
+fn test_top_level_summary() {
+  let mut opml = nested_document();
+  opml.body.push_folder("Empty Feeds").outlines.push(Outline {
+    text: "Not a Feed".to_string(),
+    ..Outline::default()
+  });
+  opml.body.push_folder("Not a Folder");
+  opml.add_feed("Another Feed", "http://example.com/another");
+  opml
+    .body
+    .push_folder("")
+    .add_feed("Unnamed Folder Feed", "http://example.com/unnamed");
+
+  let summary = opml.top_level_summary();
+  assert_eq!(
+    summary.folders,
+    vec![
+      ("Group".to_string(), 1),
+      ("Empty Feeds".to_string(), 0),
+      ("".to_string(), 1),
+    ]
+  );
+  assert_eq!(summary.loose, 2);
+
+  let mut opml = OPML::default();
+  assert_eq!(opml.top_level_summary(), TopLevelSummary::default());
+  opml
+    .body
+    .push_folder("Folder")
+    .add_feed("Feed", "http://example.com/");
+  let summary = opml.top_level_summary();
+  assert_eq!(summary.folders, vec![("Folder".to_string(), 1)]);
+  assert_eq!(summary.loose, 0);
+}
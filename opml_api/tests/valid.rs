@@ -16,6 +16,7 @@ fn test_minimum_valid_opml() {
           ..Outline::default()
         }]
       },
+      source_encoding: None,
     }
   );
 }
@@ -36,6 +37,7 @@ fn test_valid_empty_docs() {
           ..Outline::default()
         }]
       },
+      source_encoding: None,
     }
   )
 }
@@ -93,10 +95,13 @@ fn test_valid_opml_with_everything() {
             title: Some("Nested Outline Title".to_string()),
             version: Some("Nested Outline Version".to_string()),
             url: Some("Nested Outline URL".to_string()),
-            outlines: vec![]
-          }]
+            outlines: vec![],
+            original_index: None,
+          }],
+          original_index: None,
         }]
       },
+      source_encoding: None,
     }
   )
 }
@@ -116,16 +121,34 @@ fn test_valid_opml_1_0() {
           ..Outline::default()
         }]
       },
+      source_encoding: None,
     }
   );
 }
 
+#[test]
+fn test_valid_version_with_whitespace() {
+  let xml = r#"<opml version="2.0 "><head/><body><outline text="Outline"/></body></opml>"#;
+  let document = OPML::from_str(xml).unwrap();
+  assert_eq!(document.version, "2.0");
+}
+
 #[test]
 fn test_valid_from_reader() {
   let xml = r#"<opml version="2.0"><head/><body><outline text="Outline"/></body></opml>"#;
   assert!(OPML::from_reader(&mut xml.as_bytes()).is_ok());
 }
 
+#[test]
+fn test_valid_from_bytes_with_bom() {
+  let xml = read("tests/samples/minimum_valid_opml.opml").unwrap();
+  let bytes = [b"\xEF\xBB\xBF".as_slice(), xml.as_bytes()].concat();
+  assert_eq!(
+    OPML::from_bytes(&bytes).unwrap(),
+    OPML::from_str(&xml).unwrap()
+  );
+}
+
 #[test]
 fn test_valid_to_writer() {
   let document = OPML::default();
@@ -133,3 +156,123 @@ fn test_valid_to_writer() {
   assert!(document.to_writer(&mut writer).is_ok());
   assert!(!writer.is_empty());
 }
+
+#[test]
+fn test_valid_head_window_fields() {
+  let document =
+    OPML::from_str(&read("tests/samples/head_window_fields.opml").unwrap())
+      .unwrap();
+
+  let expected_head = Head {
+    vert_scroll_state: None,
+    window_top: None,
+    window_left: Some(-20),
+    window_bottom: Some(-1),
+    window_right: Some(400),
+    ..Head::default()
+  };
+  assert_eq!(document.head, Some(expected_head));
+
+  let round_tripped = OPML::from_str(&document.to_string().unwrap()).unwrap();
+  assert_eq!(round_tripped, document);
+}
+
+#[test]
+fn test_head_window_state() {
+  let document =
+    OPML::from_str(&read("tests/samples/head_window_fields.opml").unwrap())
+      .unwrap();
+  let mut head = document.head.unwrap();
+
+  let window_state = head.window_state().unwrap();
+  assert_eq!(
+    window_state,
+    WindowState {
+      expansion_state: vec![],
+      vert_scroll_state: None,
+      top: None,
+      left: Some(-20),
+      bottom: Some(-1),
+      right: Some(400),
+    }
+  );
+
+  head.set_window_state(WindowState {
+    expansion_state: vec![1, 4],
+    top: Some(0),
+    ..window_state
+  });
+  assert_eq!(head.expansion_state.as_deref(), Some("1,4"));
+  assert_eq!(head.window_top, Some(0));
+  assert_eq!(head.window_left, Some(-20));
+
+  head.set_window_state(WindowState::default());
+  assert_eq!(head, Head::default());
+  assert_eq!(head.window_state(), None);
+}
+
+#[test]
+fn test_valid_from_str_multi() {
+  let samples = [
+    "tests/spec_samples/states.opml",
+    "tests/samples/minimum_valid_opml.opml",
+    "tests/spec_samples/category.opml",
+  ]
+  .map(|sample| read(sample).unwrap());
+
+  let documents = OPML::from_str_multi(&samples.join("\n")).unwrap();
+  let expected = samples
+    .iter()
+    .map(|sample| OPML::from_str(sample).unwrap())
+    .collect::<Vec<_>>();
+  assert_eq!(documents, expected);
+  assert_eq!(documents[0].source_encoding.as_deref(), Some("ISO-8859-1"));
+  assert_eq!(documents[1].source_encoding, None);
+}
+
+#[test]
+fn test_valid_from_str_multi_processing_instruction() {
+  // The processing instruction before the second document must not make the
+  // first document's declaration look like the second document's.
+  let xml = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+    r#"<opml version="2.0"><head/><body><outline text="One"/></body></opml>"#,
+    "<?pi x?>\n",
+    r#"<opml version="2.0"><head/><body><outline text="Two"/></body></opml>"#,
+  );
+
+  let documents = OPML::from_str_multi(xml).unwrap();
+  assert_eq!(documents.len(), 2);
+  assert_eq!(documents[0].body.outlines[0].text, "One");
+  assert_eq!(documents[0].source_encoding.as_deref(), Some("UTF-8"));
+  assert_eq!(documents[1].body.outlines[0].text, "Two");
+  assert_eq!(documents[1].source_encoding, None);
+}
+
+#[test]
+fn test_valid_from_str_multi_stylesheet() {
+  let xml = concat!(
+    r#"<?xml version="1.0" encoding="ISO-8859-1"?>"#,
+    r#"<?xml-stylesheet type="text/xsl" href="opml.xsl"?>"#,
+    r#"<opml version="2.0"><head/><body><outline text="One"/></body></opml>"#,
+    "<!-- <opml version=\"2.0\"> -->\n",
+    r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+    r#"<?xml-stylesheet type="text/xsl" href="opml.xsl"?>"#,
+    r#"<opml version="2.0"><head/><body><outline text="Two"/></body></opml>"#,
+    "\n",
+    r#"<opml version="1.0"><head/><body><outline text="Three"/></body></opml>"#,
+  );
+
+  let documents = OPML::from_str_multi(xml).unwrap();
+  let texts = documents
+    .iter()
+    .map(|document| document.body.outlines[0].text.as_str())
+    .collect::<Vec<_>>();
+  assert_eq!(texts, vec!["One", "Two", "Three"]);
+
+  let encodings = documents
+    .iter()
+    .map(|document| document.source_encoding.as_deref())
+    .collect::<Vec<_>>();
+  assert_eq!(encodings, vec![Some("ISO-8859-1"), Some("UTF-8"), None]);
+}
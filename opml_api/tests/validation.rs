@@ -0,0 +1,112 @@
+use std::fs::read_to_string as read;
+
+use opml::*;
+
+#[test]
+fn test_validate_samples() {
+  for sample in [
+    "tests/samples/empty_docs.opml",
+    "tests/samples/minimum_valid_opml.opml",
+    "tests/spec_samples/category.opml",
+    "tests/spec_samples/states.opml",
+  ] {
+    let document = OPML::from_str(&read(sample).unwrap()).unwrap();
+    assert_eq!(document.validate(), vec![], "{sample}");
+  }
+}
+
+#[test]
+fn test_validate_docs_url() {
+  let mut opml = OPML::default();
+
+  for (docs, valid) in [
+    ("http://dev.opml.org/spec2.html", true),
+    ("https://opml.org/spec2.opml#1629042198000", true),
+    ("  ", true),
+    ("dev.opml.org/spec2.html", false),
+    ("http://", false),
+    ("http://dev.opml.org/spec 2.html", false),
+    ("1http://dev.opml.org", false),
+  ] {
+    opml.head = Some(Head {
+      docs: Some(docs.to_string()),
+      ..Head::default()
+    });
+
+    let expected = if valid {
+      vec![]
+    } else {
+      vec![ValidationError::InvalidDocsUrl(docs.to_string())]
+    };
+    assert_eq!(opml.validate(), expected, "{docs:?}");
+  }
+}
+
+#[cfg(feature = "date")]
+#[test]
+fn test_validate_dates() {
+  let mut opml = OPML {
+    head: Some(Head {
+      date_created: Some("Mon, 27 Feb 2006 12:09:48 GMT".to_string()),
+      date_modified: Some("2006-02-27T12:09:48Z".to_string()),
+      ..Head::default()
+    }),
+    ..OPML::default()
+  };
+  opml.body.outlines.push(Outline {
+    text: "Folder".to_string(),
+    created: Some("27 Feb 2006 12:09:48 +0100".to_string()),
+    outlines: vec![Outline {
+      text: "Feed".to_string(),
+      created: Some("yesterday".to_string()),
+      ..Outline::default()
+    }],
+    ..Outline::default()
+  });
+
+  assert_eq!(
+    opml.validate(),
+    vec![
+      ValidationError::InvalidDate {
+        field: "dateModified",
+        value: "2006-02-27T12:09:48Z".to_string(),
+      },
+      ValidationError::InvalidDate {
+        field: "created",
+        value: "yesterday".to_string(),
+      },
+    ]
+  );
+}
+
+#[test]
+fn test_validate_categories() {
+  for (category, valid) in [
+    ("/Boston/Weather", true),
+    ("/Harvard/Berkman,/Politics", true),
+    ("rust, programming", true),
+    ("  ", true),
+    ("a,,b", false),
+    ("a,", false),
+    ("/a//b", false),
+    ("a/", false),
+    ("/", false),
+  ] {
+    let mut opml = OPML::default();
+    opml.body.outlines.push(Outline {
+      text: "Outline".to_string(),
+      category: Some(category.to_string()),
+      ..Outline::default()
+    });
+
+    let expected = if valid {
+      vec![]
+    } else {
+      vec![ValidationError::MalformedCategory {
+        outline: "Outline".to_string(),
+        value: category.to_string(),
+      }]
+    };
+    assert_eq!(opml.validate(), expected, "{category:?}");
+  }
+}
@@ -1,7 +1,11 @@
-use std::{fs::read_to_string, path::PathBuf};
+use std::{
+  fs::read_to_string,
+  io::{stdout, Write},
+  path::PathBuf,
+};
 
 use clap::Parser;
-use opml::{Outline, OPML};
+use opml::OPML;
 
 #[derive(Debug, Parser)]
 #[clap(about, author, version)]
@@ -23,6 +27,13 @@ struct Args {
   #[clap(long, group = "format", required = true)]
   rss: bool,
 
+  /// Only output the outline text and xmlUrl attributes separated by a tab,
+  /// one outline per line, when both are present in the outline element.
+  /// Backslashes, tabs and line breaks in the values are escaped. With
+  /// --verbose, skipped outlines are reported on stderr.
+  #[clap(long, group = "format", required = true)]
+  tsv: bool,
+
   /// Print extra information while running.
   #[clap(long)]
   verbose: bool,
@@ -38,12 +49,9 @@ fn main() {
   let opml = OPML::from_str(&xml).expect("Failed to parse OPML file");
 
   if args.rss {
-    // Get all the outlines from the OPML document.
-    let outlines = extract_all_outlines(&opml.body.outlines);
-
     // Print out the text and xmlUrl attributes when possible.
-    for outline in outlines {
-      if let Some(xml_url) = outline.xml_url {
+    for outline in opml.outlines() {
+      if let Some(xml_url) = &outline.xml_url {
         println!("{}", outline.text);
         println!("{}", xml_url);
       } else if args.verbose {
@@ -53,31 +61,43 @@ fn main() {
         );
       }
     }
-  } else if args.json {
-    println!(
-      "{}",
-      serde_json::to_string(&opml).expect("Failed to convert OPML to JSON")
-    );
-  } else if args.json_pretty {
-    println!(
-      "{}",
-      serde_json::to_string_pretty(&opml)
-        .expect("Failed to convert OPML to pretty JSON")
-    );
+  } else if args.tsv {
+    for outline in opml.outlines() {
+      if let Some(xml_url) = &outline.xml_url {
+        println!("{}\t{}", escape_tsv(&outline.text), escape_tsv(xml_url));
+      } else if args.verbose {
+        // Keep stdout to the two columns so it can be piped.
+        eprintln!(
+          "Skipping \"{}\" because it did not have an xmlUrl attribute.",
+          outline.text
+        );
+      }
+    }
+  } else if args.json || args.json_pretty {
+    // Write the JSON straight to stdout so large documents don't have to be
+    // converted to a string first.
+    let mut stdout = stdout().lock();
+
+    if args.json {
+      serde_json::to_writer(&mut stdout, &opml)
+        .expect("Failed to convert OPML to JSON");
+    } else {
+      serde_json::to_writer_pretty(&mut stdout, &opml)
+        .expect("Failed to convert OPML to pretty JSON");
+    }
+
+    writeln!(stdout).expect("Failed to write to stdout");
   } else {
     unreachable!();
   }
 }
 
-/// A helper function that takes in `opml::Outline` elements and returns all
-/// children it can find in a single `Vec<Outline>`.
-pub fn extract_all_outlines(outlines: &[Outline]) -> Vec<Outline> {
-  let mut accumulator = vec![];
-
-  for outline in outlines {
-    accumulator.push(outline.clone());
-    accumulator.append(&mut extract_all_outlines(&outline.outlines));
-  }
-
-  accumulator
+/// Escapes backslashes, tabs and line breaks as `\\`, `\t`, `\n` and `\r`, so
+/// every feed stays on its own line with exactly two columns.
+fn escape_tsv(value: &str) -> String {
+  value
+    .replace('\\', "\\\\")
+    .replace('\t', "\\t")
+    .replace('\n', "\\n")
+    .replace('\r', "\\r")
 }
@@ -4,9 +4,9 @@ use {
 
 const SAMPLE: &str = "tests/sample.opml";
 
-#[test_case(&["--file", SAMPLE, "--json"], "json" ; "json")]
-#[test_case(&["--file", SAMPLE, "--json-pretty"], "json_pretty" ; "json_pretty")]
 #[test_case(&["--file", SAMPLE, "--rss"], "rss" ; "rss")]
+#[test_case(&["--file", SAMPLE, "--tsv"], "tsv" ; "tsv")]
+#[test_case(&["--file", "tests/tsv_escapes.opml", "--tsv"], "tsv_escapes" ; "tsv_escapes")]
 fn test_valid(args: &[&str], name: &str) {
   let mut cmd = Command::cargo_bin("opml").unwrap();
   let assert = cmd.args(args).assert().success().code(0);
@@ -14,6 +14,34 @@ fn test_valid(args: &[&str], name: &str) {
   assert_display_snapshot!(name, output);
 }
 
+// The JSON keys depend on the `serde-camel-case` feature, so both settings
+// have their own snapshots.
+#[test_case(&["--file", SAMPLE, "--json"], "json" ; "json")]
+#[test_case(&["--file", SAMPLE, "--json-pretty"], "json_pretty" ; "json_pretty")]
+fn test_valid_json(args: &[&str], name: &str) {
+  let name = if cfg!(feature = "serde-camel-case") {
+    format!("{name}_camel_case")
+  } else {
+    name.to_string()
+  };
+
+  let mut cmd = Command::cargo_bin("opml").unwrap();
+  let assert = cmd.args(args).assert().success().code(0);
+  let output = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+  assert_display_snapshot!(name, output);
+}
+
+#[test_case(&["--file", "tests/verbose.opml", "--tsv", "--verbose"], "tsv_verbose" ; "tsv_verbose")]
+fn test_valid_verbose(args: &[&str], name: &str) {
+  let mut cmd = Command::cargo_bin("opml").unwrap();
+  let assert = cmd.args(args).assert().success().code(0);
+  let output = assert.get_output();
+  let stdout = String::from_utf8(output.stdout.clone()).unwrap();
+  let stderr = String::from_utf8(output.stderr.clone()).unwrap();
+  let output = format!("stdout:\n{stdout}\nstderr:\n{stderr}");
+  assert_display_snapshot!(name, output);
+}
+
 #[test_case(&["--rss"], "missing_file" ; "missing_file")]
 #[test_case(&["--file", SAMPLE], "missing_format" ; "missing_format")]
 #[test_case(&["--rss", "--json"], "multiple_formats" ; "multiple_formats")]